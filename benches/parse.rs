@@ -0,0 +1,28 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use cookie::{Cookie, SetCookie};
+
+const SET_COOKIE: &str =
+    "sid=YWJjZA==; Domain=example.com; Max-Age=3600; Path=/; SameSite=Lax; HttpOnly; Secure";
+
+fn set_cookie(c: &mut Criterion) {
+    let header_values = vec![SET_COOKIE; 1000];
+
+    c.bench_function("SetCookie::from 1000 headers", |b| {
+        b.iter(|| SetCookie::from(black_box(&header_values).iter()))
+    });
+}
+
+fn cookie(c: &mut Criterion) {
+    let header_value = (0..20)
+        .map(|i| format!("key{i}=value{i}"))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    c.bench_function("Cookie::from_str 20 pairs", |b| {
+        b.iter(|| black_box(&header_value).parse::<Cookie>())
+    });
+}
+
+criterion_group!(benches, set_cookie, cookie);
+criterion_main!(benches);
@@ -0,0 +1,98 @@
+#[cfg(not(feature = "std"))]
+use alloc::borrow::ToOwned;
+#[cfg(all(test, not(feature = "std")))]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use http::HeaderValue;
+
+use crate::{cookie::parse_pairs, Cookie, CookieError};
+
+/// same as `Cookie`, but borrows names and values from the header value
+///
+/// for read-only extraction, see `into_owned`
+#[derive(Debug, Default, Clone)]
+pub struct BorrowedCookie<'a> {
+    inner: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> BorrowedCookie<'a> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// raw `Cookie` header value, e.g. `key1=value1; key2=value2`
+    pub fn parse(x: &'a str) -> Result<Self, CookieError> {
+        let mut inner = HashMap::new();
+
+        for key_value in parse_pairs(x) {
+            let (key, value) = key_value?;
+            inner.insert(key, value);
+        }
+
+        Ok(Self { inner })
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_header_value(x: &'a HeaderValue) -> Result<Self, CookieError> {
+        Self::parse(x.to_str().map_err(|_| CookieError::NotVisibleAscii)?)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.inner.get(key).copied()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.inner.iter().map(|(key, value)| (*key, *value))
+    }
+
+    pub fn into_owned(self) -> Cookie {
+        self.inner
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<Cookie>()
+    }
+}
+
+#[test]
+fn test_borrowed_cookie() {
+    let header_value = String::from("a=1; b = 2 ; ; =3");
+    let cookie = BorrowedCookie::parse(&header_value).unwrap();
+
+    assert_eq!(cookie.len(), 2);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+    assert_eq!(cookie.get("c"), None);
+
+    // borrowed from the header value, not allocated
+    let range = header_value.as_bytes().as_ptr_range();
+    let value = cookie.get("b").unwrap();
+    assert!(range.contains(&value.as_ptr()));
+
+    let owned = cookie.into_owned();
+    assert_eq!(owned.get("a"), Some("1"));
+    assert_eq!(owned.get("b"), Some("2"));
+    assert_eq!(owned.len(), 2);
+
+    assert!(matches!(
+        BorrowedCookie::parse("a=1; b"),
+        Err(CookieError::MissingEquals(x)) if x == "b"
+    ));
+}
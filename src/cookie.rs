@@ -1,16 +1,187 @@
+#[cfg(feature = "actix-extractor")]
+pub mod actix;
 #[cfg(feature = "axum-extractor")]
 pub mod axum;
 #[cfg(feature = "poem-extractor")]
 pub mod poem;
+#[cfg(feature = "warp-extractor")]
+pub mod warp;
 
-use std::collections::HashMap;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::ToOwned,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(all(test, not(feature = "std")))]
+use alloc::{format, vec};
+use core::{fmt::Display, iter::FusedIterator, str::FromStr};
+#[cfg(feature = "std")]
+use std::collections::{hash_map, BTreeMap, HashMap};
 
+#[cfg(not(feature = "std"))]
+use hashbrown::{hash_map, HashMap};
+#[cfg(feature = "std")]
 use http::{
     header::{self, HeaderName, HeaderValue, InvalidHeaderValue},
     HeaderMap,
 };
 use itertools::Itertools;
 
+#[cfg(feature = "std")]
+use crate::set_cookie::parse_set_cookie;
+use crate::{validate::validate, CookieError, CookieValidationError, SetCookie, SetCookieOptions};
+
+/// extractor configuration, read from request extensions
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CookieConfig {
+    /// rejects with `431 Request Header Fields Too Large` if exceeded
+    pub max_pairs: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl CookieConfig {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn max_pairs(mut self, max_pairs: usize) -> Self {
+        self.max_pairs.replace(max_pairs);
+
+        self
+    }
+
+    /// counts pairs without parsing, empty segments are skipped as `FromStr` does
+    pub fn is_exceeded(&self, headers: &HeaderMap) -> bool {
+        let max_pairs = match self.max_pairs {
+            Some(x) => x,
+            None => return false,
+        };
+
+        let pairs_len: usize = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .map(|x| {
+                x.as_bytes()
+                    .split(|b| *b == b';')
+                    .filter(|x| !x.trim_ascii().is_empty())
+                    .count()
+            })
+            .sum();
+
+        pairs_len > max_pairs
+    }
+}
+
+/// rejection of the framework extractors
+#[cfg(any(
+    feature = "actix-extractor",
+    feature = "axum-extractor",
+    feature = "poem-extractor",
+    feature = "warp-extractor"
+))]
+#[derive(Debug)]
+pub enum CookieRejection {
+    /// hasn't cookie header
+    Missing,
+    /// cookie header is present but failed to parse
+    Invalid,
+    /// exceeded `CookieConfig::max_pairs`, only axum and poem
+    TooManyPairs,
+}
+
+#[cfg(any(
+    feature = "actix-extractor",
+    feature = "axum-extractor",
+    feature = "poem-extractor",
+    feature = "warp-extractor"
+))]
+impl CookieRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CookieRejection::Missing => "CookieRejection: missing cookie header",
+            CookieRejection::Invalid => "CookieRejection: invalid cookie header",
+            CookieRejection::TooManyPairs => "CookieRejection: too many cookie pairs",
+        }
+    }
+
+    /// `400 Bad Request`, or `431 Request Header Fields Too Large` for `TooManyPairs`
+    pub fn status(&self) -> http::StatusCode {
+        match self {
+            CookieRejection::Missing | CookieRejection::Invalid => http::StatusCode::BAD_REQUEST,
+            CookieRejection::TooManyPairs => http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "actix-extractor",
+    feature = "axum-extractor",
+    feature = "poem-extractor",
+    feature = "warp-extractor"
+))]
+impl Display for CookieRejection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(any(
+    feature = "actix-extractor",
+    feature = "axum-extractor",
+    feature = "poem-extractor",
+    feature = "warp-extractor"
+))]
+impl std::error::Error for CookieRejection {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// extractor yielding an empty `Cookie` instead of rejecting if the cookie header is absent,
+/// malformed headers are still rejected
+///
+/// to customize the rejection status, wrap `Cookie` and map its `CookieRejection`
+///
+/// ```ignore
+/// struct AuthCookie(Cookie);
+///
+/// #[async_trait::async_trait]
+/// impl<S: Send + Sync> FromRequestParts<S> for AuthCookie {
+///     type Rejection = StatusCode;
+///
+///     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+///         Cookie::from_request_parts(parts, state)
+///             .await
+///             .map(AuthCookie)
+///             .map_err(|_| StatusCode::UNAUTHORIZED)
+///     }
+/// }
+/// ```
+#[cfg(any(feature = "axum-extractor", feature = "poem-extractor"))]
+#[derive(Debug, Default, Clone)]
+pub struct OptionalCookie(pub Cookie);
+
+#[cfg(any(feature = "axum-extractor", feature = "poem-extractor"))]
+impl OptionalCookie {
+    pub fn into_inner(self) -> Cookie {
+        self.0
+    }
+}
+
+#[cfg(any(feature = "axum-extractor", feature = "poem-extractor"))]
+impl std::ops::Deref for OptionalCookie {
+    type Target = Cookie;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[derive(Debug, Default, Clone)]
 pub struct Cookie {
     inner: HashMap<String, String>,
@@ -21,74 +192,199 @@ impl Cookie {
         Default::default()
     }
 
+    /// entries with an empty name are skipped, empty if hasn't cookie or set-cookie in headers
+    ///
     /// returns None
     /// - if header_name is not cookie or set-cookie
-    /// - if hasn't cookie or set-cookie in headers
     /// - if failed to `header_value.to_str()`
     /// - if failed to parse cookie
+    #[cfg(feature = "std")]
     pub fn from_headers(header_name: HeaderName, headers: &HeaderMap) -> Option<Self> {
+        match Cookie::try_from_headers(header_name, headers) {
+            Ok(x) => Some(x.unwrap_or_default()),
+            Err(err) => {
+                tracing::debug!("failed to parse Cookie: {}", err);
+                None
+            }
+        }
+    }
+
+    /// `Cookie` header of any `http::Request`
+    ///
+    /// None if hasn't cookie header or failed to parse it, unlike `from_headers`
+    /// which returns an empty `Cookie` if the header is absent
+    #[cfg(feature = "std")]
+    pub fn from_http_request<B>(req: &http::Request<B>) -> Option<Self> {
+        Cookie::try_from_headers(header::COOKIE, req.headers())
+            .ok()
+            .flatten()
+    }
+
+    /// - `Ok(None)` if hasn't cookie or set-cookie in headers
+    /// - `Err` if cookie header is present but failed to parse, malformed set-cookie values are skipped
+    #[cfg(feature = "std")]
+    pub fn try_from_headers(
+        header_name: HeaderName,
+        headers: &HeaderMap,
+    ) -> Result<Option<Self>, CookieError> {
+        // HTTP/2 may split the cookie header into multiple lines
         if let header::COOKIE = header_name {
-            let x = headers.get(header_name);
-            return match x {
-                Some(x) => Cookie::from_cookie(x),
-                None => Some(Cookie::new()),
-            };
+            if !headers.contains_key(header::COOKIE) {
+                return Ok(None);
+            }
+
+            let mut cookie = Cookie::new();
+            for x in headers.get_all(header::COOKIE) {
+                cookie.merge(Cookie::from_cookie(x)?);
+            }
+            return Ok(Some(cookie));
         }
 
         if let header::SET_COOKIE = header_name {
-            let xs = headers
-                .iter()
-                .filter_map(|(header_name, x)| (header_name == header::SET_COOKIE).then_some(x));
-            return Cookie::from_set_cookie(xs);
+            if !headers.contains_key(header::SET_COOKIE) {
+                return Ok(None);
+            }
+
+            let xs = headers.get_all(header::SET_COOKIE).iter();
+            return Ok(Some(Cookie::from_set_cookie(xs)));
         }
 
-        None
+        Err(CookieError::UnsupportedHeaderName(header_name))
     }
 
-    fn from_cookie(x: &HeaderValue) -> Option<Self> {
-        // key1=avchdef; key2=qwehkdfsjd
-        // key1=afjkd
+    /// from json object of string to string
+    ///
+    /// numbers and bools are coerced to their string forms, other values are errors
+    #[cfg(feature = "serde")]
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, CookieError> {
+        let object = value.as_object().ok_or(CookieError::NotAnObject)?;
 
-        let mut inner = HashMap::new();
+        object
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(x) => x.clone(),
+                    serde_json::Value::Number(x) => x.to_string(),
+                    serde_json::Value::Bool(x) => x.to_string(),
+                    _ => return Err(CookieError::InvalidJsonValue(key.clone())),
+                };
+
+                Ok((key.clone(), value))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
+    fn from_cookie(x: &HeaderValue) -> Result<Self, CookieError> {
+        x.to_str()
+            .map_err(|_| CookieError::NotVisibleAscii)?
+            .parse()
+    }
 
-        let x = x.to_str().ok()?;
-        for key_value in x.split(';') {
-            let (key, value) = key_value.split_once('=')?;
+    /// from `Cookie` header values, skips values which are non visible ascii or malformed
+    #[cfg(feature = "std")]
+    pub fn from_header_values<'a>(it: impl Iterator<Item = &'a HeaderValue>) -> Self {
+        let mut cookie = Cookie::new();
 
-            inner.insert(key.trim().to_owned(), value.to_owned());
+        for x in it {
+            match Cookie::from_cookie(x) {
+                Ok(x) => cookie.merge(x),
+                Err(err) => tracing::debug!("skipped Cookie: {}", err),
+            }
         }
 
-        Self { inner }.into()
+        cookie
     }
 
-    fn from_set_cookie<'a, I>(xs: I) -> Option<Self>
+    /// raw `Cookie` header value without `http`, None if malformed, see `FromStr`
+    pub fn parse_header(x: &str) -> Option<Self> {
+        x.parse().ok()
+    }
+
+    /// never fails, invalid utf-8 is replaced with `U+FFFD`, malformed pairs are skipped
+    #[cfg(feature = "std")]
+    pub fn from_header_value_lossy(x: &HeaderValue) -> Self {
+        parse_pairs(&String::from_utf8_lossy(x.as_bytes()))
+            .filter_map(Result::ok)
+            .collect()
+    }
+
+    /// same as `SetCookie::from_header_values`, skips malformed values
+    #[cfg(feature = "std")]
+    fn from_set_cookie<'a, I>(xs: I) -> Self
     where
         I: Iterator<Item = &'a HeaderValue>,
     {
         // Set-Cookie: key1=value; Max-Age=12345; Domain=eeeee.com; HttpOnly; Secure
         // Set-Cookie: key2=value
 
-        let mut inner = HashMap::new();
-
-        for x in xs {
-            let key_value = x.to_str().ok()?.split(';').next()?;
-
-            let (key, value) = key_value.split_once('=')?;
-
-            inner.insert(key.to_owned(), value.to_owned());
-        }
-
-        Self { inner }.into()
+        xs.filter_map(|x| match x.to_str() {
+            Ok(x) => Some(x),
+            Err(_) => {
+                tracing::debug!("skipped non visible ascii Set-Cookie: {:?}", x);
+                None
+            }
+        })
+        .filter_map(parse_set_cookie)
+        .map(|(key, value, _)| (key, value))
+        .collect()
     }
 
     pub fn add(&mut self, key: &str, value: &str) {
         self.inner.insert(key.to_owned(), value.to_owned());
     }
 
+    /// value is percent-encoded, read it with `get_decoded`
+    #[cfg(feature = "encode")]
+    pub fn add_encoded(&mut self, key: &str, value: &str) {
+        self.inner
+            .insert(key.to_owned(), crate::encode::encode(value));
+    }
+
+    /// same as `add`, but returns the previous value
+    pub fn replace(&mut self, key: &str, value: &str) -> Option<String> {
+        self.inner.insert(key.to_owned(), value.to_owned())
+    }
+
     pub fn get(&self, key: &str) -> Option<&str> {
         self.inner.get(key).map(|st| &**st)
     }
 
+    /// O(n), for legacy clients sending inconsistent casing, prefer `get`
+    pub fn get_ignore_case(&self, key: &str) -> Option<&str> {
+        self.inner
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// decoded as base64url without padding
+    #[cfg(feature = "base64")]
+    pub fn get_base64(&self, key: &str) -> Option<Result<Vec<u8>, base64::DecodeError>> {
+        use base64::Engine;
+
+        self.get(key)
+            .map(|x| base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(x))
+    }
+
+    /// percent-decoded
+    #[cfg(feature = "encode")]
+    pub fn get_decoded(&self, key: &str) -> Option<std::borrow::Cow<'_, str>> {
+        self.get(key).map(crate::encode::decode)
+    }
+
+    /// None if missing or the signature is invalid
+    #[cfg(feature = "signed")]
+    pub fn get_signed(&self, signed: &crate::SignedCookie, key: &str) -> Option<String> {
+        self.get(key).and_then(|x| signed.verify(key, x))
+    }
+
+    /// None if missing or failed to decrypt
+    #[cfg(feature = "encrypted")]
+    pub fn get_encrypted(&self, encrypted: &crate::EncryptedCookie, key: &str) -> Option<String> {
+        self.get(key).and_then(|x| encrypted.decrypt(key, x))
+    }
+
     pub fn get2(&self, key1: &str, key2: &str) -> Option<(&str, &str)> {
         self.get(key1).and_then(|x| Some((x, self.get(key2)?)))
     }
@@ -97,6 +393,19 @@ impl Cookie {
         self.inner.remove(key)
     }
 
+    /// returns whether the key was present
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.inner.remove(key).is_some()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
     pub fn len(&self) -> usize {
         self.inner.len()
     }
@@ -105,12 +414,70 @@ impl Cookie {
         self.inner.is_empty()
     }
 
-    /// used `format!` macro
-    pub fn to_str(&self) -> String {
+    /// `other` wins on collision
+    pub fn merge(&mut self, other: Cookie) {
+        self.inner.extend(other.inner);
+    }
+
+    /// `self` wins on collision
+    pub fn merge_preserve(&mut self, other: Cookie) {
+        for (key, value) in other.inner {
+            self.inner.entry(key).or_insert(value);
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    /// deletion directive (empty value, `Max-Age=0`) for each name, scoped by path/domain of options
+    pub fn into_set_cookie_deletions(self, options: SetCookieOptions) -> SetCookie {
+        self.inner
+            .into_keys()
+            .fold(SetCookie::new(), |set_cookie, key| {
+                set_cookie.expire(key, options.clone())
+            })
+    }
+
+    /// keys colliding when compared case-insensitively, grouped by lowercased key
+    pub fn duplicate_ignoring_case(&self) -> Vec<(String, Vec<String>)> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for key in self.inner.keys() {
+            groups.entry(key.to_lowercase()).or_default().push(key);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, keys)| keys.len() > 1)
+            .map(|(lowercased, keys)| (lowercased, keys.into_iter().cloned().sorted().collect()))
+            .collect()
+    }
+
+    /// every entry violating RFC 6265, sorted by name
+    pub fn validate_all(&self) -> Vec<(String, CookieValidationError)> {
         self.inner
             .iter()
-            .map(|(key, value)| format!("{}={}", key, value))
-            .join(";")
+            .filter_map(|(key, value)| Some((key.clone(), validate(key, value).err()?)))
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .collect()
+    }
+
+    /// arbitrary order, use `iter_sorted` for deterministic order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// sorted by key, compared as bytes
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.iter()
+            .sorted_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()))
+    }
+
+    /// same as `Display`
+    pub fn to_str(&self) -> String {
+        self.to_string()
     }
 
     /// used `+` operator
@@ -118,7 +485,62 @@ impl Cookie {
         self.inner
             .into_iter()
             .map(|(key, value)| key + "=" + &value)
-            .join(";")
+            .join("; ")
+    }
+}
+
+impl Display for Cookie {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, (key, value)) in self.inner.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}={}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// trimmed pairs of raw `Cookie` header value, entries with an empty name are skipped
+///
+/// a single pair of surrounding double quotes is stripped from values, as RFC 6265 allows
+pub(crate) fn parse_pairs(x: &str) -> impl Iterator<Item = Result<(&str, &str), CookieError>> {
+    // key1=avchdef; key2=qwehkdfsjd
+    // key1=afjkd
+
+    x.split(';')
+        .filter(|key_value| !key_value.trim().is_empty())
+        .map(|key_value| {
+            key_value
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), unquote(value.trim())))
+                .ok_or_else(|| CookieError::MissingEquals(key_value.trim().to_owned()))
+        })
+        // empty names are invalid, skip them rather than failing the whole header
+        .filter(|x| !matches!(x, Ok(("", _))))
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+impl FromStr for Cookie {
+    type Err = CookieError;
+
+    /// raw `Cookie` header value, e.g. `key1=value1; key2=value2`
+    fn from_str(x: &str) -> Result<Self, Self::Err> {
+        let mut inner = HashMap::new();
+
+        for key_value in parse_pairs(x) {
+            let (key, value) = key_value?;
+            inner.insert(key.to_owned(), value.to_owned());
+        }
+
+        Ok(Self { inner })
     }
 }
 
@@ -151,6 +573,40 @@ impl<'a> FromIterator<(String, &'a str)> for Cookie {
     }
 }
 
+/// owned iterator over the pairs of a `Cookie`, in arbitrary order
+#[derive(Debug)]
+pub struct CookieIntoIter {
+    inner: hash_map::IntoIter<String, String>,
+}
+
+impl Iterator for CookieIntoIter {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CookieIntoIter {}
+
+impl FusedIterator for CookieIntoIter {}
+
+impl IntoIterator for Cookie {
+    type Item = (String, String);
+    type IntoIter = CookieIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CookieIntoIter {
+            inner: self.inner.into_iter(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryInto<HeaderValue> for Cookie {
     type Error = InvalidHeaderValue;
 
@@ -159,7 +615,8 @@ impl TryInto<HeaderValue> for Cookie {
     }
 }
 
-impl<'a> TryInto<HeaderValue> for &'a Cookie {
+#[cfg(feature = "std")]
+impl TryInto<HeaderValue> for &Cookie {
     type Error = InvalidHeaderValue;
 
     fn try_into(self) -> Result<HeaderValue, Self::Error> {
@@ -167,6 +624,7 @@ impl<'a> TryInto<HeaderValue> for &'a Cookie {
     }
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_from_cookie() {
     let x = "madome_access_token=avchdef; madome_refresh_token=qwehkdfsjd";
@@ -180,6 +638,7 @@ fn test_from_cookie() {
     assert_eq!(cookie.get("madome_refresh_token"), Some("qwehkdfsjd"));
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_from_cookie_without_semicolon() {
     let x = "madome_access_token=avchdef";
@@ -192,6 +651,7 @@ fn test_from_cookie_without_semicolon() {
     assert_eq!(cookie.get("madome_access_token"), Some("avchdef"));
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_from_set_cookie() {
     let xs = [
@@ -210,3 +670,572 @@ fn test_from_set_cookie() {
     assert_eq!(cookie.get("madome_access_token"), Some("admjsher"));
     assert_eq!(cookie.get("madome_refresh_token"), Some("kfadbhe"));
 }
+
+#[test]
+fn test_iter_sorted() {
+    let a = Cookie::from_iter([("b", "2"), ("a", "1"), ("c", "3")]);
+    let b = Cookie::from_iter([("c", "3"), ("b", "2"), ("a", "1")]);
+
+    let expected = vec![("a", "1"), ("b", "2"), ("c", "3")];
+
+    assert_eq!(a.iter_sorted().collect::<Vec<_>>(), expected);
+    assert_eq!(b.iter_sorted().collect::<Vec<_>>(), expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_cookie_skip_empty_name() {
+    let x = "=noname; a=1";
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, x.try_into().unwrap());
+
+    let cookie = Cookie::from_headers(header::COOKIE, &headers).unwrap();
+
+    assert_eq!(cookie.len(), 1);
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    let cookie: Cookie = "=value; a=1".parse().unwrap();
+    assert_eq!(cookie.iter().collect::<Vec<_>>(), vec![("a", "1")]);
+
+    let mut headers = HeaderMap::new();
+    headers.append(header::SET_COOKIE, "=value; Path=/".try_into().unwrap());
+    headers.append(header::SET_COOKIE, " =value".try_into().unwrap());
+    headers.append(header::SET_COOKIE, "a=1".try_into().unwrap());
+
+    let cookie = Cookie::from_headers(header::SET_COOKIE, &headers).unwrap();
+    assert_eq!(cookie.iter().collect::<Vec<_>>(), vec![("a", "1")]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_set_cookie_same_as_set_cookie() {
+    let mut headers = HeaderMap::new();
+    headers.append(header::SET_COOKIE, "a = 1 ; Path=/".try_into().unwrap());
+    headers.append(header::SET_COOKIE, "novalue; Secure".try_into().unwrap());
+    headers.append(header::SET_COOKIE, "b=2".try_into().unwrap());
+
+    let cookie = Cookie::from_headers(header::SET_COOKIE, &headers).unwrap();
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2")]
+    );
+
+    let set_cookie = SetCookie::from_headers(&headers);
+    assert_eq!(set_cookie.get("a"), Some("1"));
+    assert_eq!(set_cookie.get("b"), Some("2"));
+    assert_eq!(set_cookie.len(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_into_set_cookie_deletions() {
+    let cookie = Cookie::from_iter([("a", "1"), ("b", "2"), ("c", "3")]);
+
+    let set_cookie = cookie.into_set_cookie_deletions(SetCookieOptions::new().path("/"));
+
+    for key in ["a", "b", "c"] {
+        assert_eq!(set_cookie.get(key), Some(""));
+    }
+
+    let mut entries = set_cookie.into_entries();
+    entries.sort_by(|x, y| x.0.cmp(&y.0));
+
+    assert_eq!(entries.len(), 3);
+    for (_, _, options) in entries {
+        assert_eq!(options, SetCookieOptions::new().path("/").max_age(0));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_cookie_config_max_pairs() {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, "a=1; b=2; c=3".try_into().unwrap());
+
+    assert!(!CookieConfig::new().is_exceeded(&headers));
+    assert!(!CookieConfig::new().max_pairs(3).is_exceeded(&headers));
+    assert!(CookieConfig::new().max_pairs(2).is_exceeded(&headers));
+
+    headers.insert(header::COOKIE, "a=1; b=2; ; ".try_into().unwrap());
+    assert!(!CookieConfig::new().max_pairs(2).is_exceeded(&headers));
+    assert!(CookieConfig::new().max_pairs(1).is_exceeded(&headers));
+}
+
+#[test]
+fn test_duplicate_ignoring_case() {
+    let cookie = Cookie::from_iter([("Session", "1"), ("session", "2"), ("theme", "dark")]);
+
+    assert_eq!(
+        cookie.duplicate_ignoring_case(),
+        vec![(
+            "session".to_owned(),
+            vec!["Session".to_owned(), "session".to_owned()]
+        )]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_from_json() {
+    let value = serde_json::json!({ "a": "1", "b": 2, "c": true });
+
+    let cookie = Cookie::from_json(&value).unwrap();
+
+    assert_eq!(cookie.len(), 3);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+    assert_eq!(cookie.get("c"), Some("true"));
+
+    let value = serde_json::json!({ "a": "1", "b": ["x"] });
+
+    assert!(matches!(
+        Cookie::from_json(&value),
+        Err(CookieError::InvalidJsonValue(key)) if key == "b"
+    ));
+    assert!(matches!(
+        Cookie::from_json(&serde_json::json!("a=1")),
+        Err(CookieError::NotAnObject)
+    ));
+}
+
+#[test]
+fn test_validate_all() {
+    let cookie = Cookie::from_iter([("sid", "abc"), ("bad name", "1"), ("theme", "a;b")]);
+
+    assert_eq!(
+        cookie.validate_all(),
+        vec![
+            ("bad name".to_owned(), CookieValidationError::InvalidName),
+            ("theme".to_owned(), CookieValidationError::InvalidValue),
+        ]
+    );
+}
+
+#[cfg(feature = "base64")]
+#[test]
+fn test_get_base64() {
+    let cookie = Cookie::from_iter([("valid", "aGVsbG8_Pw"), ("invalid", "aGVsbG8*")]);
+
+    assert_eq!(cookie.get_base64("valid").unwrap().unwrap(), b"hello??");
+    assert!(cookie.get_base64("invalid").unwrap().is_err());
+    assert!(cookie.get_base64("absent").is_none());
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let mut cookie = (0..100)
+        .map(|x| (x.to_string(), x.to_string()))
+        .collect::<Cookie>();
+
+    for x in 1..100 {
+        cookie.take(&x.to_string());
+    }
+
+    let capacity = cookie.inner.capacity();
+    cookie.shrink_to_fit();
+
+    assert!(cookie.inner.capacity() < capacity);
+    assert_eq!(cookie.get("0"), Some("0"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_try_from_headers() {
+    let headers = HeaderMap::new();
+    assert!(matches!(
+        Cookie::try_from_headers(header::COOKIE, &headers),
+        Ok(None)
+    ));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, "a=1; b=2".try_into().unwrap());
+    let cookie = Cookie::try_from_headers(header::COOKIE, &headers)
+        .unwrap()
+        .unwrap();
+    assert_eq!(cookie.len(), 2);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, "a=1; b".try_into().unwrap());
+    assert!(matches!(
+        Cookie::try_from_headers(header::COOKIE, &headers),
+        Err(CookieError::MissingEquals(x)) if x == "b"
+    ));
+    assert!(Cookie::from_headers(header::COOKIE, &headers).is_none());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, HeaderValue::from_bytes(b"a=\xff").unwrap());
+    assert!(matches!(
+        Cookie::try_from_headers(header::COOKIE, &headers),
+        Err(CookieError::NotVisibleAscii)
+    ));
+
+    assert!(matches!(
+        Cookie::try_from_headers(header::HOST, &headers),
+        Err(CookieError::UnsupportedHeaderName(x)) if x == header::HOST
+    ));
+}
+
+#[test]
+fn test_replace() {
+    let mut cookie = Cookie::new();
+
+    assert_eq!(cookie.replace("token", "old"), None);
+    assert_eq!(cookie.replace("token", "new"), Some("old".to_owned()));
+    assert_eq!(cookie.get("token"), Some("new"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_header_value_lossy() {
+    let x = HeaderValue::from_bytes(b"a=1; b=\xff; c; d=4; e=\"x\"").unwrap();
+
+    let cookie = Cookie::from_header_value_lossy(&x);
+
+    assert_eq!(cookie.len(), 4);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("\u{FFFD}"));
+    assert_eq!(cookie.get("d"), Some("4"));
+    assert_eq!(cookie.get("e"), Some("x"));
+
+    let x = HeaderValue::from_static(r#"a="x"; b=2"#);
+    assert_eq!(
+        Cookie::from_header_value_lossy(&x)
+            .iter_sorted()
+            .collect::<Vec<_>>(),
+        Cookie::from_str(x.to_str().unwrap())
+            .unwrap()
+            .iter_sorted()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_header_values() {
+    let xs = [
+        HeaderValue::from_static("a=1; b=2"),
+        HeaderValue::from_bytes(b"c=\xff").unwrap(),
+        HeaderValue::from_static("d=4"),
+    ];
+
+    let cookie = Cookie::from_header_values(xs.iter());
+
+    assert_eq!(cookie.len(), 3);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("d"), Some("4"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_cookie_trim_value() {
+    let x = "a=1; b=2;  c=3 ";
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, x.try_into().unwrap());
+
+    let cookie = Cookie::from_headers(header::COOKIE, &headers).unwrap();
+
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+    assert_eq!(cookie.get("c"), Some("3"));
+}
+
+#[cfg(feature = "encode")]
+#[test]
+fn test_encoded_round_trip() {
+    let mut cookie = Cookie::new();
+    cookie.add_encoded("a", "a; b=c");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::COOKIE, cookie.to_str().try_into().unwrap());
+
+    let cookie = Cookie::from_headers(header::COOKIE, &headers).unwrap();
+
+    assert_eq!(cookie.get("a"), Some("a%3B%20b%3Dc"));
+    assert_eq!(cookie.get_decoded("a").as_deref(), Some("a; b=c"));
+}
+
+#[test]
+fn test_from_str() {
+    let cookie: Cookie = "".parse().unwrap();
+    assert!(cookie.is_empty());
+
+    let cookie: Cookie = "a=1".parse().unwrap();
+    assert_eq!(cookie.len(), 1);
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    let err = "a=1; b".parse::<Cookie>().unwrap_err();
+    assert!(matches!(err, CookieError::MissingEquals(x) if x == "b"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+
+    let json = serde_json::to_value(&cookie).unwrap();
+    assert_eq!(json, serde_json::json!({ "a": "1", "b": "2" }));
+
+    let deserialized: Cookie = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        deserialized.iter_sorted().collect::<Vec<_>>(),
+        cookie.iter_sorted().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_get_ignore_case() {
+    let cookie = Cookie::from_iter([("Session", "1")]);
+
+    assert_eq!(cookie.get("session"), None);
+    assert_eq!(cookie.get_ignore_case("session"), Some("1"));
+    assert_eq!(cookie.get_ignore_case("other"), None);
+}
+
+#[test]
+fn test_to_str_round_trip() {
+    let cookie = Cookie::from_iter([("a", "1"), ("b", "2"), ("c", "3")]);
+
+    let parsed: Cookie = cookie.to_str().parse().unwrap();
+    assert_eq!(
+        parsed.iter_sorted().collect::<Vec<_>>(),
+        cookie.iter_sorted().collect::<Vec<_>>()
+    );
+
+    let parsed: Cookie = cookie.clone().into_str().parse().unwrap();
+    assert_eq!(
+        parsed.iter_sorted().collect::<Vec<_>>(),
+        cookie.iter_sorted().collect::<Vec<_>>()
+    );
+
+    assert_eq!(Cookie::from_iter([("a", "1")]).to_str(), "a=1");
+}
+
+#[test]
+fn test_remove() {
+    let mut cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+
+    assert!(cookie.remove("a"));
+    assert!(!cookie.remove("a"));
+    assert_eq!(cookie.get("a"), None);
+    assert_eq!(cookie.len(), 1);
+}
+
+#[test]
+fn test_contains_key() {
+    let cookie = Cookie::from_iter([("a", "1")]);
+
+    assert!(cookie.contains_key("a"));
+    assert!(!cookie.contains_key("A"));
+    assert!(!cookie.contains_key("b"));
+}
+
+#[test]
+fn test_clear() {
+    let mut cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+    cookie.clear();
+
+    assert!(cookie.is_empty());
+    assert!(!cookie.contains_key("a"));
+}
+
+#[test]
+fn test_iter() {
+    let cookie = Cookie::from_iter([("b", "2"), ("a", "1"), ("c", "3")]);
+
+    let mut xs = cookie.iter().collect::<Vec<_>>();
+    xs.sort();
+    assert_eq!(xs, vec![("a", "1"), ("b", "2"), ("c", "3")]);
+
+    let mut xs = cookie.into_iter().collect::<Vec<_>>();
+    xs.sort();
+    assert_eq!(
+        xs,
+        vec![
+            ("a".to_owned(), "1".to_owned()),
+            ("b".to_owned(), "2".to_owned()),
+            ("c".to_owned(), "3".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_from_cookie_value_with_equals() {
+    let cookie: Cookie = "sid=YWJjZA==; a=b=c".parse().unwrap();
+
+    assert_eq!(cookie.get("sid"), Some("YWJjZA=="));
+    assert_eq!(cookie.get("a"), Some("b=c"));
+}
+
+#[cfg(feature = "signed")]
+#[test]
+fn test_signed_round_trip() {
+    let signed = crate::SignedCookie::new("secret");
+    let set_cookie = SetCookie::new().set_signed(&signed, "sid", "1", SetCookieOptions::new());
+    let headers = set_cookie.try_into_headers().unwrap();
+    let cookie = Cookie::from_headers(header::SET_COOKIE, &headers).unwrap();
+
+    assert_eq!(cookie.get_signed(&signed, "sid"), Some("1".to_owned()));
+    assert_eq!(cookie.get_signed(&signed, "missing"), None);
+
+    let cookie = Cookie::from_iter([("sid", "1")]);
+    assert_eq!(cookie.get_signed(&signed, "sid"), None);
+}
+
+#[cfg(feature = "encrypted")]
+#[test]
+fn test_encrypted_round_trip() {
+    let encrypted = crate::EncryptedCookie::new(&[7; 32]);
+    let set_cookie =
+        SetCookie::new().set_encrypted(&encrypted, "sid", "1", SetCookieOptions::new());
+    let headers = set_cookie.try_into_headers().unwrap();
+    let cookie = Cookie::from_headers(header::SET_COOKIE, &headers).unwrap();
+
+    assert_eq!(
+        cookie.get_encrypted(&encrypted, "sid"),
+        Some("1".to_owned())
+    );
+    assert_eq!(cookie.get_encrypted(&encrypted, "missing"), None);
+}
+
+#[test]
+fn test_display() {
+    let cookie = Cookie::from_iter([("a", "1")]);
+    assert_eq!(format!("{cookie}"), "a=1");
+    assert_eq!(format!("{}", Cookie::new()), "");
+
+    let cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+    let st = format!("{cookie}");
+    assert!(st == "a=1; b=2" || st == "b=2; a=1");
+    assert_eq!(st, cookie.to_str());
+    assert_eq!(st, cookie.into_str());
+}
+
+#[test]
+fn test_merge() {
+    let mut cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+    cookie.merge(Cookie::from_iter([("b", "3"), ("c", "4")]));
+
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("3"));
+    assert_eq!(cookie.get("c"), Some("4"));
+}
+
+#[test]
+fn test_merge_preserve() {
+    let mut cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+    cookie.merge_preserve(Cookie::from_iter([("b", "3"), ("c", "4")]));
+
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+    assert_eq!(cookie.get("c"), Some("4"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_multiple_cookie_headers() {
+    let mut headers = HeaderMap::new();
+    headers.append(header::COOKIE, HeaderValue::from_static("a=1; b=2"));
+    headers.append(header::COOKIE, HeaderValue::from_static("c=3"));
+
+    let cookie = Cookie::from_headers(header::COOKIE, &headers).unwrap();
+
+    assert_eq!(cookie.len(), 3);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+    assert_eq!(cookie.get("c"), Some("3"));
+
+    headers.append(header::COOKIE, HeaderValue::from_static("d"));
+    assert!(Cookie::try_from_headers(header::COOKIE, &headers).is_err());
+}
+
+#[test]
+fn test_parse_header() {
+    let cookie = Cookie::parse_header("a=1").unwrap();
+    assert_eq!(cookie.len(), 1);
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    let cookie = Cookie::parse_header("a=1; b=2; c=3").unwrap();
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2"), ("c", "3")]
+    );
+
+    assert!(Cookie::parse_header("").unwrap().is_empty());
+    assert!(Cookie::parse_header("a=1; b").is_none());
+}
+
+#[test]
+fn test_from_cookie_quoted_value() {
+    let cookie: Cookie = r#"sid="abc"; a=abc"; b=""; c="; d=""abc"""#.parse().unwrap();
+
+    assert_eq!(cookie.get("sid"), Some("abc"));
+    assert_eq!(cookie.get("a"), Some("abc\""));
+    assert_eq!(cookie.get("b"), Some(""));
+    assert_eq!(cookie.get("c"), Some("\""));
+    assert_eq!(cookie.get("d"), Some("\"abc\""));
+
+    let cookie: Cookie = "sid=abc".parse().unwrap();
+    assert_eq!(cookie.get("sid"), Some("abc"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_http_request() {
+    let req = http::Request::builder()
+        .header(header::COOKIE, "a=1; b=2")
+        .body(())
+        .unwrap();
+
+    let cookie = Cookie::from_http_request(&req).unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+
+    let req = http::Request::builder().body(()).unwrap();
+    assert!(Cookie::from_http_request(&req).is_none());
+
+    let req = http::Request::builder()
+        .header(header::COOKIE, "a")
+        .body(())
+        .unwrap();
+    assert!(Cookie::from_http_request(&req).is_none());
+}
+
+#[test]
+fn test_into_iter_exact_size() {
+    let cookie = Cookie::from_iter([("a", "1"), ("b", "2")]);
+
+    let mut it: CookieIntoIter = cookie.into_iter();
+    assert_eq!(it.len(), 2);
+
+    it.next();
+    assert_eq!(it.len(), 1);
+
+    assert!(it.next().is_some());
+    assert!(it.next().is_none());
+    assert!(it.next().is_none());
+}
+
+#[cfg(any(
+    feature = "actix-extractor",
+    feature = "axum-extractor",
+    feature = "poem-extractor",
+    feature = "warp-extractor"
+))]
+#[test]
+fn test_cookie_rejection() {
+    let rejection = CookieRejection::TooManyPairs;
+    assert_eq!(
+        rejection.to_string(),
+        "CookieRejection: too many cookie pairs"
+    );
+    assert_eq!(
+        rejection.status(),
+        http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+
+    let rejection: Box<dyn std::error::Error> = Box::new(CookieRejection::Missing);
+    assert_eq!(
+        rejection.to_string(),
+        "CookieRejection: missing cookie header"
+    );
+}
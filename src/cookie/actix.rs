@@ -0,0 +1,67 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, http::StatusCode, FromRequest, HttpRequest, ResponseError};
+use http::{header, HeaderMap, HeaderValue};
+
+use crate::{Cookie, CookieRejection};
+
+impl ResponseError for CookieRejection {
+    // actix-web is on http 0.2
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status().as_u16()).unwrap_or(StatusCode::BAD_REQUEST)
+    }
+}
+
+impl FromRequest for Cookie {
+    type Error = CookieRejection;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // actix-web is on http 0.2
+        let mut headers = HeaderMap::new();
+        for x in request
+            .headers()
+            .get_all(actix_web::http::header::COOKIE)
+            .filter_map(|x| HeaderValue::from_bytes(x.as_bytes()).ok())
+        {
+            headers.append(header::COOKIE, x);
+        }
+
+        let cookie = Cookie::try_from_headers(header::COOKIE, &headers)
+            .map_err(|_| CookieRejection::Invalid)
+            .and_then(|x| x.ok_or(CookieRejection::Missing));
+
+        ready(cookie)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_from_request() {
+    use actix_web::test::TestRequest;
+
+    let request = TestRequest::default()
+        .insert_header((actix_web::http::header::COOKIE, "a=1; b=2"))
+        .to_http_request();
+
+    let cookie = Cookie::extract(&request).await.unwrap();
+
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+
+    let request = TestRequest::default()
+        .insert_header((actix_web::http::header::COOKIE, "a"))
+        .to_http_request();
+
+    let rejection = Cookie::extract(&request).await.unwrap_err();
+
+    assert!(matches!(rejection, CookieRejection::Invalid));
+    assert_eq!(rejection.status_code(), StatusCode::BAD_REQUEST);
+
+    let request = TestRequest::default().to_http_request();
+
+    let rejection = Cookie::extract(&request).await.unwrap_err();
+
+    assert!(matches!(rejection, CookieRejection::Missing));
+    assert_eq!(rejection.status_code(), StatusCode::BAD_REQUEST);
+}
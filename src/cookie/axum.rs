@@ -1,28 +1,13 @@
-use std::fmt::Display;
-
 use axum::{extract::FromRequestParts, response::IntoResponse};
-use http::{header, request::Parts, StatusCode};
-
-use crate::Cookie;
-
-#[derive(Debug)]
-pub struct CookieRejection;
-
-impl Display for CookieRejection {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("CookieRejection")
-    }
-}
+#[cfg(test)]
+use http::StatusCode;
+use http::{header, request::Parts};
 
-impl std::error::Error for CookieRejection {
-    fn description(&self) -> &str {
-        "CookieRejection"
-    }
-}
+use crate::{Cookie, CookieConfig, CookieRejection, OptionalCookie};
 
 impl IntoResponse for CookieRejection {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        (self.status(), self.to_string()).into_response()
     }
 }
 
@@ -33,7 +18,159 @@ where
 {
     type Rejection = CookieRejection;
 
+    /// parsed once per request, cached in request extensions
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        Cookie::from_headers(header::COOKIE, &parts.headers).ok_or(CookieRejection)
+        if let Some(cookie) = parts.extensions.get::<Cookie>() {
+            return Ok(cookie.clone());
+        }
+
+        let config = parts
+            .extensions
+            .get::<CookieConfig>()
+            .copied()
+            .unwrap_or_default();
+
+        if config.is_exceeded(&parts.headers) {
+            return Err(CookieRejection::TooManyPairs);
+        }
+
+        let cookie = Cookie::try_from_headers(header::COOKIE, &parts.headers)
+            .map_err(|_| CookieRejection::Invalid)?
+            .ok_or(CookieRejection::Missing)?;
+
+        parts.extensions.insert(cookie.clone());
+
+        Ok(cookie)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for OptionalCookie
+where
+    S: Send + Sync,
+{
+    type Rejection = CookieRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Cookie::from_request_parts(parts, state).await {
+            Ok(cookie) => Ok(OptionalCookie(cookie)),
+            Err(CookieRejection::Missing) => Ok(OptionalCookie::default()),
+            Err(rejection) => Err(rejection),
+        }
     }
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_cached_in_extensions() {
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a=1")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let cookie = Cookie::from_request_parts(&mut parts, &()).await.unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    // not parsed again
+    parts
+        .headers
+        .insert(header::COOKIE, "a=2".try_into().unwrap());
+
+    let cookie = Cookie::from_request_parts(&mut parts, &()).await.unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_cached_preseeded_extension() {
+    // e.g. inserted by a middleware
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a=2")
+        .extension(Cookie::from_iter([("a", "1")]))
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let cookie = Cookie::from_request_parts(&mut parts, &()).await.unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_max_pairs() {
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a=1; b=2; c=3")
+        .extension(CookieConfig::new().max_pairs(2))
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let rejection = Cookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        rejection.into_response().status(),
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_rejection_status() {
+    let (mut parts, _) = http::Request::builder().body(()).unwrap().into_parts();
+
+    let rejection = Cookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(rejection, CookieRejection::Missing));
+    assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let rejection = Cookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(rejection, CookieRejection::Invalid));
+    assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_optional_cookie() {
+    let (mut parts, _) = http::Request::builder().body(()).unwrap().into_parts();
+
+    let cookie = OptionalCookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert!(cookie.is_empty());
+
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a=1")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let cookie = OptionalCookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    let (mut parts, _) = http::Request::builder()
+        .header(header::COOKIE, "a")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let rejection = OptionalCookie::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+    assert!(matches!(rejection, CookieRejection::Invalid));
+}
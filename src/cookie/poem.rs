@@ -1,35 +1,106 @@
-use std::fmt::Display;
-
 use http::header;
 use poem::{error::ResponseError, http::StatusCode, FromRequest, Request, RequestBody};
 
-use crate::Cookie;
-
-#[derive(Debug)]
-pub struct CookieRejection;
-
-impl Display for CookieRejection {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("CookieRejection")
-    }
-}
-
-impl std::error::Error for CookieRejection {
-    fn description(&self) -> &str {
-        "CookieRejection"
-    }
-}
+use crate::{Cookie, CookieConfig, CookieRejection, OptionalCookie};
 
 impl ResponseError for CookieRejection {
     fn status(&self) -> StatusCode {
-        StatusCode::INTERNAL_SERVER_ERROR
+        CookieRejection::status(self)
     }
 }
 
 impl<'a> FromRequest<'a> for Cookie {
     async fn from_request(request: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
-        Cookie::from_headers(header::COOKIE, request.headers())
-            .ok_or(CookieRejection)
+        let config = request
+            .extensions()
+            .get::<CookieConfig>()
+            .copied()
+            .unwrap_or_default();
+
+        if config.is_exceeded(request.headers()) {
+            return Err(CookieRejection::TooManyPairs.into());
+        }
+
+        Cookie::try_from_headers(header::COOKIE, request.headers())
+            .map_err(|_| CookieRejection::Invalid)?
+            .ok_or(CookieRejection::Missing)
             .map_err(Into::into)
     }
 }
+
+impl<'a> FromRequest<'a> for OptionalCookie {
+    async fn from_request(request: &'a Request, body: &mut RequestBody) -> poem::Result<Self> {
+        match Cookie::from_request(request, body).await {
+            Ok(cookie) => Ok(OptionalCookie(cookie)),
+            Err(err) if matches!(err.downcast_ref(), Some(CookieRejection::Missing)) => {
+                Ok(OptionalCookie::default())
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_max_pairs() {
+    let request = Request::builder()
+        .header(header::COOKIE, "a=1; b=2; c=3")
+        .extension(CookieConfig::new().max_pairs(2))
+        .finish();
+
+    let err = Cookie::from_request_without_body(&request)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_rejection_status() {
+    let request = Request::builder().finish();
+
+    let err = Cookie::from_request_without_body(&request)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<CookieRejection>(),
+        Some(CookieRejection::Missing)
+    ));
+    assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+
+    let request = Request::builder().header(header::COOKIE, "a").finish();
+
+    let err = Cookie::from_request_without_body(&request)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<CookieRejection>(),
+        Some(CookieRejection::Invalid)
+    ));
+    assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_optional_cookie() {
+    let request = Request::builder().finish();
+    let cookie = OptionalCookie::from_request_without_body(&request)
+        .await
+        .unwrap();
+    assert!(cookie.is_empty());
+
+    let request = Request::builder().header(header::COOKIE, "a=1").finish();
+    let cookie = OptionalCookie::from_request_without_body(&request)
+        .await
+        .unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+
+    let request = Request::builder().header(header::COOKIE, "a").finish();
+    let err = OptionalCookie::from_request_without_body(&request)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+}
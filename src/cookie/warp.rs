@@ -0,0 +1,47 @@
+use http::{header, HeaderMap};
+use warp::{reject::Reject, Filter, Rejection};
+
+use crate::{Cookie, CookieRejection};
+
+impl Reject for CookieRejection {}
+
+/// rejects with `CookieRejection`, recover it to respond
+pub fn cookie() -> impl Filter<Extract = (Cookie,), Error = Rejection> + Clone {
+    warp::header::headers_cloned().and_then(|headers: HeaderMap| async move {
+        Cookie::try_from_headers(header::COOKIE, &headers)
+            .map_err(|_| CookieRejection::Invalid)
+            .and_then(|x| x.ok_or(CookieRejection::Missing))
+            .map_err(warp::reject::custom)
+    })
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_cookie_filter() {
+    let extracted = warp::test::request()
+        .header("cookie", "a=1; b=2")
+        .filter(&cookie())
+        .await
+        .unwrap();
+
+    assert_eq!(extracted.get("a"), Some("1"));
+    assert_eq!(extracted.get("b"), Some("2"));
+
+    let rejection = warp::test::request().filter(&cookie()).await.unwrap_err();
+
+    assert!(matches!(
+        rejection.find::<CookieRejection>(),
+        Some(CookieRejection::Missing)
+    ));
+
+    let rejection = warp::test::request()
+        .header("cookie", "a")
+        .filter(&cookie())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        rejection.find::<CookieRejection>(),
+        Some(CookieRejection::Invalid)
+    ));
+}
@@ -0,0 +1,29 @@
+use std::borrow::Cow;
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// everything that isn't a cookie-octet, and `%`, `=`
+const COOKIE_VALUE: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b',')
+    .add(b';')
+    .add(b'\\')
+    .add(b'%')
+    .add(b'=');
+
+pub(crate) fn encode(value: &str) -> String {
+    utf8_percent_encode(value, COOKIE_VALUE).to_string()
+}
+
+/// invalid utf-8 is replaced with `U+FFFD`
+pub(crate) fn decode(value: &str) -> Cow<'_, str> {
+    percent_decode_str(value).decode_utf8_lossy()
+}
+
+#[test]
+fn test_encode() {
+    assert_eq!(encode("a; b=c"), "a%3B%20b%3Dc");
+    assert_eq!(decode("a%3B%20b%3Dc"), "a; b=c");
+    assert_eq!(encode("plain"), "plain");
+}
@@ -0,0 +1,106 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+const NONCE_LEN: usize = 12;
+
+/// encrypts cookie values with ChaCha20-Poly1305, so they're confidential and tamper-proof
+///
+/// the encrypted value is base64 of `nonce + ciphertext`, the cookie name is the associated data
+#[derive(Clone)]
+pub struct EncryptedCookie {
+    cipher: ChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for EncryptedCookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedCookie").finish_non_exhaustive()
+    }
+}
+
+impl EncryptedCookie {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+        }
+    }
+
+    pub fn encrypt(&self, name: &str, value: &str) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::fill(&mut nonce).expect("failed to generate nonce");
+
+        let payload = Payload {
+            msg: value.as_bytes(),
+            aad: name.as_bytes(),
+        };
+        // only fails if the plaintext is too large, which a cookie can't be
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .unwrap();
+
+        let mut buf = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(&ciphertext);
+
+        URL_SAFE_NO_PAD.encode(buf)
+    }
+
+    /// None if the value was tampered, encrypted under another name or with another key
+    pub fn decrypt(&self, name: &str, value: &str) -> Option<String> {
+        let buf = URL_SAFE_NO_PAD.decode(value).ok()?;
+        if buf.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = buf.split_at(NONCE_LEN);
+
+        let payload = Payload {
+            msg: ciphertext,
+            aad: name.as_bytes(),
+        };
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .ok()?;
+
+        String::from_utf8(plaintext).ok()
+    }
+}
+
+#[test]
+fn test_encrypted_round_trip() {
+    let encrypted = EncryptedCookie::new(&[7; 32]);
+    let value = encrypted.encrypt("sid", "user=1; admin");
+
+    assert!(!value.contains("user"));
+    assert!(crate::validate::is_cookie_value(&value));
+    assert_eq!(
+        encrypted.decrypt("sid", &value),
+        Some("user=1; admin".to_owned())
+    );
+    assert_ne!(encrypted.encrypt("sid", "user=1; admin"), value);
+}
+
+#[test]
+fn test_encrypted_tampered() {
+    let encrypted = EncryptedCookie::new(&[7; 32]);
+    let value = encrypted.encrypt("sid", "1");
+
+    let mut buf = URL_SAFE_NO_PAD.decode(&value).unwrap();
+    *buf.last_mut().unwrap() ^= 1;
+    let tampered = URL_SAFE_NO_PAD.encode(buf);
+
+    assert_eq!(encrypted.decrypt("sid", &tampered), None);
+    assert_eq!(encrypted.decrypt("sid", "short"), None);
+    assert_eq!(EncryptedCookie::new(&[8; 32]).decrypt("sid", &value), None);
+}
+
+#[test]
+fn test_encrypted_cross_name() {
+    let encrypted = EncryptedCookie::new(&[7; 32]);
+    let value = encrypted.encrypt("sid", "1");
+
+    assert_eq!(encrypted.decrypt("other", &value), None);
+}
@@ -0,0 +1,70 @@
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[cfg(feature = "std")]
+use http::HeaderName;
+
+/// crate-level error of parsing and validation
+#[derive(Debug, thiserror::Error)]
+pub enum CookieError {
+    #[error("header value contains non visible ascii")]
+    NotVisibleAscii,
+    #[error("missing `=` in `{0}`")]
+    MissingEquals(String),
+    /// only cookie and set-cookie
+    #[cfg(feature = "std")]
+    #[error("unsupported header name `{0}`")]
+    UnsupportedHeaderName(HeaderName),
+    #[error("invalid SameSite `{0}`")]
+    InvalidSameSite(String),
+    #[error("invalid Priority `{0}`")]
+    InvalidPriority(String),
+    #[error("expected a json object")]
+    NotAnObject,
+    /// only strings, numbers and bools are allowed as value
+    #[error("invalid json value of `{0}`")]
+    InvalidJsonValue(String),
+    /// empty or not a token
+    #[error("invalid cookie name `{0}`")]
+    InvalidName(String),
+    /// contains non cookie-octet, with the cookie name
+    #[error("invalid cookie value of `{0}`")]
+    InvalidValue(String),
+    #[error(transparent)]
+    PrefixViolation(#[from] PrefixError),
+}
+
+/// RFC 6265 violations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CookieValidationError {
+    #[error("empty cookie name")]
+    EmptyName,
+    #[error("cookie name isn't a token")]
+    InvalidName,
+    #[error("cookie value contains invalid octet")]
+    InvalidValue,
+}
+
+/// replaced by `CookieError`
+#[deprecated(note = "use `CookieError`")]
+pub type CookieParseError = CookieError;
+
+/// `__Secure-` and `__Host-` cookie name prefix rules, with the cookie name
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrefixError {
+    #[error("`{0}` must be secure")]
+    SecureRequired(String),
+    #[error("`{0}` must not have domain")]
+    DomainNotAllowed(String),
+    #[error("`{0}` must have path `/`")]
+    PathMustBeRoot(String),
+}
+
+/// the formatted Set-Cookie line of cookie `name` is `size` bytes, browsers drop it
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("`{name}` is {size} bytes, exceeds {max_size} bytes")]
+pub struct CookieSizeError {
+    pub name: String,
+    pub size: usize,
+    pub max_size: usize,
+}
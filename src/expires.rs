@@ -0,0 +1,106 @@
+//! `Expires` attribute date type
+//!
+//! - `expires-time` (default): `time::OffsetDateTime`
+//! - `expires-chrono`: `chrono::DateTime<chrono::Utc>`
+//! - neither: raw `String`, emitted and parsed verbatim
+//!
+//! `expires-time` takes precedence if both are enabled
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::ToOwned, string::String};
+
+#[cfg(feature = "expires-time")]
+pub type Expires = time::OffsetDateTime;
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+pub type Expires = chrono::DateTime<chrono::Utc>;
+
+#[cfg(not(any(feature = "expires-time", feature = "expires-chrono")))]
+pub type Expires = String;
+
+#[cfg(feature = "expires-time")]
+const IMF_FIXDATE: &[time::format_description::FormatItem<'static>] = time::macros::format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+const IMF_FIXDATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+#[cfg(feature = "expires-time")]
+pub(crate) fn fmt_expires(expires: &Expires) -> String {
+    expires
+        .to_offset(time::UtcOffset::UTC)
+        .format(IMF_FIXDATE)
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "expires-time")]
+pub(crate) fn parse_expires(st: &str) -> Option<Expires> {
+    time::PrimitiveDateTime::parse(st, IMF_FIXDATE)
+        .ok()
+        .map(|x| x.assume_utc())
+}
+
+/// IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+pub(crate) fn fmt_expires(expires: &Expires) -> String {
+    expires.format(IMF_FIXDATE).to_string()
+}
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+pub(crate) fn parse_expires(st: &str) -> Option<Expires> {
+    chrono::NaiveDateTime::parse_from_str(st, IMF_FIXDATE)
+        .ok()
+        .map(|x| x.and_utc())
+}
+
+#[cfg(feature = "expires-time")]
+pub(crate) fn add_seconds(expires: Expires, seconds: i64) -> Expires {
+    expires + time::Duration::seconds(seconds)
+}
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+pub(crate) fn add_seconds(expires: Expires, seconds: i64) -> Expires {
+    expires + chrono::TimeDelta::seconds(seconds)
+}
+
+#[cfg(not(any(feature = "expires-time", feature = "expires-chrono")))]
+pub(crate) fn fmt_expires(expires: &Expires) -> String {
+    expires.clone()
+}
+
+#[cfg(not(any(feature = "expires-time", feature = "expires-chrono")))]
+pub(crate) fn parse_expires(st: &str) -> Option<Expires> {
+    Some(st.to_owned())
+}
+
+#[cfg(feature = "expires-time")]
+#[test]
+fn expires_time() {
+    let expires = time::macros::datetime!(1994-11-06 08:49:37 UTC);
+
+    assert_eq!(fmt_expires(&expires), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(
+        parse_expires("Sun, 06 Nov 1994 08:49:37 GMT"),
+        Some(expires)
+    );
+    assert_eq!(parse_expires("06/11/1994"), None);
+}
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+#[test]
+fn expires_chrono() {
+    use chrono::TimeZone;
+
+    let expires = chrono::Utc
+        .with_ymd_and_hms(1994, 11, 6, 8, 49, 37)
+        .unwrap();
+
+    assert_eq!(fmt_expires(&expires), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(
+        parse_expires("Sun, 06 Nov 1994 08:49:37 GMT"),
+        Some(expires)
+    );
+    assert_eq!(parse_expires("06/11/1994"), None);
+}
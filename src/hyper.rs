@@ -0,0 +1,61 @@
+#[cfg(test)]
+use ::hyper::header;
+use ::hyper::{Request, Response};
+
+use crate::{Cookie, SetCookie};
+
+/// same as `Cookie::from_http_request`
+pub fn cookies_from_request<B>(req: &Request<B>) -> Option<Cookie> {
+    Cookie::from_http_request(req)
+}
+
+/// appends, so existing `Set-Cookie` headers are kept
+pub fn apply_set_cookies<B>(resp: &mut Response<B>, set_cookie: &SetCookie) {
+    set_cookie.append_to(resp.headers_mut());
+}
+
+#[test]
+fn test_cookies_from_request() {
+    let req = Request::builder()
+        .header(header::COOKIE, "a=1; b=2")
+        .body(())
+        .unwrap();
+
+    let cookie = cookies_from_request(&req).unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+
+    let req = Request::builder().body(()).unwrap();
+    assert!(cookies_from_request(&req).is_none());
+
+    let req = Request::builder()
+        .header(header::COOKIE, "a")
+        .body(())
+        .unwrap();
+    assert!(cookies_from_request(&req).is_none());
+}
+
+#[test]
+fn test_apply_set_cookies() {
+    use crate::SetCookieOptions;
+
+    let mut resp = Response::builder()
+        .header(header::SET_COOKIE, "existing=1")
+        .body(())
+        .unwrap();
+
+    let set_cookie = SetCookie::new().set("a", "1", SetCookieOptions::new()).set(
+        "b",
+        "2",
+        SetCookieOptions::new().http_only(true),
+    );
+    apply_set_cookies(&mut resp, &set_cookie);
+
+    let xs = resp
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|x| x.to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(xs, vec!["existing=1", "a=1", "b=2; HttpOnly"]);
+}
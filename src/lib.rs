@@ -1,5 +1,44 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("either `std` or `alloc` feature is required");
+
+extern crate alloc;
+
+mod borrowed_cookie;
 mod cookie;
+#[cfg(feature = "encode")]
+mod encode;
+#[cfg(feature = "encrypted")]
+mod encrypted;
+mod error;
+mod expires;
+mod multi_cookie;
 mod set_cookie;
+#[cfg(feature = "signed")]
+mod signed;
+mod validate;
+
+#[cfg(feature = "hyper")]
+mod hyper;
+#[cfg(feature = "session")]
+mod session;
+#[cfg(feature = "tower")]
+mod tower;
 
+pub use borrowed_cookie::*;
 pub use cookie::*;
+#[cfg(feature = "encrypted")]
+pub use encrypted::*;
+pub use error::*;
+pub use expires::Expires;
+#[cfg(feature = "hyper")]
+pub use hyper::*;
+pub use multi_cookie::*;
+#[cfg(feature = "session")]
+pub use session::*;
 pub use set_cookie::*;
+#[cfg(feature = "signed")]
+pub use signed::*;
+#[cfg(feature = "tower")]
+pub use tower::*;
@@ -0,0 +1,98 @@
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use http::HeaderValue;
+
+use crate::{cookie::parse_pairs, Cookie, CookieError};
+
+/// same as `Cookie`, but keeps every pair in header order, including repeated names
+///
+/// e.g. `a=1; a=2` during a migration of the cookie's path or domain
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MultiCookie {
+    inner: Vec<(String, String)>,
+}
+
+impl MultiCookie {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_header_value(x: &HeaderValue) -> Result<Self, CookieError> {
+        x.to_str()
+            .map_err(|_| CookieError::NotVisibleAscii)?
+            .parse()
+    }
+
+    /// first one if there are several cookies with the same name
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.inner
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// in header order
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.inner
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.iter().any(|(k, _)| k == key)
+    }
+
+    /// counts repeated names separately
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// in header order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// last one wins if there are several cookies with the same name, as `Cookie` parses
+    pub fn into_cookie(self) -> Cookie {
+        self.inner.into_iter().collect()
+    }
+}
+
+impl FromStr for MultiCookie {
+    type Err = CookieError;
+
+    fn from_str(x: &str) -> Result<Self, Self::Err> {
+        let inner = parse_pairs(x)
+            .map(|key_value| key_value.map(|(k, v)| (k.to_owned(), v.to_owned())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { inner })
+    }
+}
+
+#[test]
+fn test_multi_cookie() {
+    let cookie: MultiCookie = "a=1; b=3; a=2".parse().unwrap();
+
+    assert_eq!(cookie.len(), 3);
+    assert_eq!(cookie.get_all("a").collect::<Vec<_>>(), ["1", "2"]);
+    assert_eq!(cookie.get_all("b").collect::<Vec<_>>(), ["3"]);
+    assert_eq!(cookie.get_all("c").count(), 0);
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert!(cookie.contains_key("b"));
+
+    let cookie = cookie.into_cookie();
+    assert_eq!(cookie.len(), 2);
+    assert_eq!(cookie.get("a"), Some("2"));
+
+    assert!("a=1; b".parse::<MultiCookie>().is_err());
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Cookie, SetCookie, SetCookieOptions};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SessionData {
+    /// None until saved for the first time
+    id: Option<String>,
+    inner: HashMap<String, String>,
+}
+
+impl SessionData {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_id(id: impl Into<String>, values: HashMap<String, String>) -> Self {
+        Self {
+            id: Some(id.into()),
+            inner: values,
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn is_new(&self) -> bool {
+        self.id.is_none()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.inner.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.inner.get(key).map(|st| &**st)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.inner.remove(key)
+    }
+
+    pub fn values(&self) -> &HashMap<String, String> {
+        &self.inner
+    }
+}
+
+/// server-side storage of sessions, keyed by the id stored in the cookie
+///
+/// e.g. for redis, `load` is `GET session:{id}` and `save` is `SET session:{id}`,
+/// generating a fresh id with `generate_session_id` when `data.id()` is None
+///
+/// ```ignore
+/// #[async_trait::async_trait]
+/// impl SessionStore for RedisStore {
+///     async fn load(&self, id: &str) -> Option<SessionData> {
+///         let values = self.client.hgetall(format!("session:{id}")).await.ok()?;
+///         Some(SessionData::with_id(id, values))
+///     }
+///
+///     async fn save(&self, data: SessionData) -> String {
+///         let id = data.id().map(ToOwned::to_owned).unwrap_or_else(generate_session_id);
+///         self.client.hset(format!("session:{id}"), data.values()).await.unwrap();
+///         id
+///     }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn load(&self, id: &str) -> Option<SessionData>;
+
+    /// returns the session id
+    async fn save(&self, data: SessionData) -> String;
+}
+
+/// 32 random hex characters
+pub fn generate_session_id() -> String {
+    let mut buf = [0u8; 16];
+    getrandom::fill(&mut buf).expect("failed to generate session id");
+
+    buf.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStore {
+    inner: Arc<Mutex<HashMap<String, SessionData>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().is_empty()
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, id: &str) -> Option<SessionData> {
+        self.inner.lock().unwrap().get(id).cloned()
+    }
+
+    async fn save(&self, mut data: SessionData) -> String {
+        let id = data.id.get_or_insert_with(generate_session_id).clone();
+
+        self.inner.lock().unwrap().insert(id.clone(), data);
+
+        id
+    }
+}
+
+/// reads the session id from `Cookie` and emits `SetCookie` for new sessions
+#[derive(Debug, Clone)]
+pub struct Session<S> {
+    store: S,
+    cookie_name: String,
+    options: SetCookieOptions,
+}
+
+impl<S> Session<S>
+where
+    S: SessionStore,
+{
+    pub fn new(store: S, cookie_name: impl Into<String>, options: SetCookieOptions) -> Self {
+        Self {
+            store,
+            cookie_name: cookie_name.into(),
+            options,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// returns a new session if cookie hasn't the session id or the store doesn't know it
+    pub async fn load(&self, cookie: &Cookie) -> SessionData {
+        match cookie.get(&self.cookie_name) {
+            Some(id) => self.store.load(id).await.unwrap_or_default(),
+            None => SessionData::new(),
+        }
+    }
+
+    /// sets the session id on `set_cookie` only if the session was new
+    pub async fn save(&self, data: SessionData, set_cookie: SetCookie) -> SetCookie {
+        let is_new = data.is_new();
+        let id = self.store.save(data).await;
+
+        if is_new {
+            set_cookie.set(&self.cookie_name, id, self.options.clone())
+        } else {
+            set_cookie
+        }
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn session_new_and_existing() {
+    let session = Session::new(
+        MemoryStore::new(),
+        "sid",
+        SetCookieOptions::new().http_only(true),
+    );
+
+    let mut data = session.load(&Cookie::new()).await;
+    assert!(data.is_new());
+    data.insert("user", "1");
+
+    let set_cookie = session.save(data, SetCookie::new()).await;
+    let id = set_cookie.get("sid").unwrap().to_owned();
+    assert_eq!(id.len(), 32);
+    assert_eq!(session.store().len(), 1);
+
+    let cookie = Cookie::from_iter([("sid", id.as_str())]);
+    let mut data = session.load(&cookie).await;
+    assert_eq!(data.id(), Some(id.as_str()));
+    assert_eq!(data.get("user"), Some("1"));
+    data.insert("user", "2");
+
+    let set_cookie = session.save(data, SetCookie::new()).await;
+    assert!(set_cookie.is_empty());
+    assert_eq!(session.store().len(), 1);
+
+    let data = session.load(&cookie).await;
+    assert_eq!(data.get("user"), Some("2"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn session_unknown_id() {
+    let session = Session::new(MemoryStore::new(), "sid", SetCookieOptions::new());
+
+    let cookie = Cookie::from_iter([("sid", "unknown")]);
+    let data = session.load(&cookie).await;
+
+    assert!(data.is_new());
+}
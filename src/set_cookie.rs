@@ -1,12 +1,37 @@
-use std::{collections::HashMap, str::FromStr};
+#[cfg(feature = "axum-extractor")]
+mod axum;
+#[cfg(feature = "std")]
+mod headers;
+#[cfg(feature = "poem-extractor")]
+mod poem;
+
+#[cfg(all(test, not(feature = "std")))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, str::FromStr};
+#[cfg(all(test, feature = "std"))]
+use http::{header, HeaderMap, HeaderValue};
+
+use itertools::Itertools;
 
-use http::{
-    header::{self, HeaderName},
-    HeaderMap, HeaderValue,
+#[cfg(feature = "std")]
+pub use headers::*;
+
+use crate::{
+    expires::{fmt_expires, parse_expires},
+    validate::{validate, validate_prefix},
+    Cookie, CookieError, CookieSizeError, CookieValidationError, Expires, PrefixError,
 };
 
-#[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SameSite {
     Strict,
     Lax,
@@ -14,7 +39,8 @@ pub enum SameSite {
 }
 
 impl SameSite {
-    fn as_str(&self) -> &'static str {
+    /// as emitted in `Set-Cookie`, e.g. `Lax`
+    pub fn as_str(&self) -> &'static str {
         match self {
             SameSite::Strict => "Strict",
             SameSite::Lax => "Lax",
@@ -23,32 +49,97 @@ impl SameSite {
     }
 }
 
+impl Display for SameSite {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// case-insensitive, so `Display` round-trips
 impl FromStr for SameSite {
-    type Err = ();
+    type Err = CookieError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let same_site = match s {
-            "strict" => Self::Strict,
-            "lax" => Self::Lax,
-            "none" => Self::None,
-            _ => return Err(()),
+        let same_site = if s.eq_ignore_ascii_case("strict") {
+            Self::Strict
+        } else if s.eq_ignore_ascii_case("lax") {
+            Self::Lax
+        } else if s.eq_ignore_ascii_case("none") {
+            Self::None
+        } else {
+            return Err(CookieError::InvalidSameSite(s.to_owned()));
         };
 
         Ok(same_site)
     }
 }
 
-#[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Clone, Default)]
+/// Chromium only, eviction order under storage pressure
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// as emitted in `Set-Cookie`, e.g. `High`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+        }
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// case-insensitive, so `Display` round-trips
+impl FromStr for Priority {
+    type Err = CookieError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let priority = if s.eq_ignore_ascii_case("low") {
+            Self::Low
+        } else if s.eq_ignore_ascii_case("medium") {
+            Self::Medium
+        } else if s.eq_ignore_ascii_case("high") {
+            Self::High
+        } else {
+            return Err(CookieError::InvalidPriority(s.to_owned()));
+        };
+
+        Ok(priority)
+    }
+}
+
+/// builder methods can be chained off an existing value, e.g. `options.clone().max_age(10)`,
+/// and `unset_*` clears an attribute
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct SetCookieOptions {
     pub http_only: bool,
     pub secure: bool,
-    // expires: ,
+    /// CHIPS, always emitted with `Secure`
+    pub partitioned: bool,
+    /// IMF-fixdate, see `Expires` for the type depending on features
+    pub expires: Option<Expires>,
     /// Seconds
     pub max_age: Option<i64>,
+    /// `Some` for domain-scoped cookies, `None` for host-only cookies
+    ///
+    /// a single leading dot is stripped on parse, as browsers do, e.g. `.example.com` -> `example.com`
     pub domain: Option<String>,
     pub path: Option<String>,
     pub same_site: Option<SameSite>,
+    pub priority: Option<Priority>,
 }
 
 impl SetCookieOptions {
@@ -56,15 +147,184 @@ impl SetCookieOptions {
         Default::default()
     }
 
+    /// opinionated baseline, `HttpOnly; Secure; SameSite=Lax; Path=/`
+    pub fn secure_defaults() -> Self {
+        Self::new()
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::Lax)
+            .path("/")
+    }
+
+    /// no attributes set
+    pub fn is_default(&self) -> bool {
+        let SetCookieOptions {
+            http_only,
+            secure,
+            partitioned,
+            expires,
+            max_age,
+            domain,
+            path,
+            same_site,
+            priority,
+        } = self;
+
+        !http_only
+            && !secure
+            && !partitioned
+            && expires.is_none()
+            && max_age.is_none()
+            && domain.is_none()
+            && path.is_none()
+            && same_site.is_none()
+            && priority.is_none()
+    }
+
+    /// changed attributes, e.g. `SameSite: Lax -> Strict`, unset attributes are shown as `-`
+    pub fn diff(&self, other: &SetCookieOptions) -> Vec<String> {
+        fn show<T>(x: &Option<T>, f: impl Fn(&T) -> String) -> String {
+            x.as_ref().map(f).unwrap_or_else(|| "-".to_owned())
+        }
+
+        let SetCookieOptions {
+            http_only,
+            secure,
+            partitioned,
+            expires,
+            max_age,
+            domain,
+            path,
+            same_site,
+            priority,
+        } = self;
+
+        let xs = [
+            (
+                "HttpOnly",
+                http_only.to_string(),
+                other.http_only.to_string(),
+            ),
+            ("Secure", secure.to_string(), other.secure.to_string()),
+            (
+                "Partitioned",
+                partitioned.to_string(),
+                other.partitioned.to_string(),
+            ),
+            (
+                "Expires",
+                show(expires, fmt_expires),
+                show(&other.expires, fmt_expires),
+            ),
+            (
+                "Max-Age",
+                show(max_age, ToString::to_string),
+                show(&other.max_age, ToString::to_string),
+            ),
+            (
+                "Domain",
+                show(domain, Clone::clone),
+                show(&other.domain, Clone::clone),
+            ),
+            (
+                "Path",
+                show(path, Clone::clone),
+                show(&other.path, Clone::clone),
+            ),
+            (
+                "SameSite",
+                show(same_site, |x| x.as_str().to_owned()),
+                show(&other.same_site, |x| x.as_str().to_owned()),
+            ),
+            (
+                "Priority",
+                show(priority, |x| x.as_str().to_owned()),
+                show(&other.priority, |x| x.as_str().to_owned()),
+            ),
+        ];
+
+        xs.into_iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(name, a, b)| format!("{}: {} -> {}", name, a, b))
+            .collect()
+    }
+
+    /// whether `st` is a known attribute, e.g. for filtering segments by hand
+    /// the parser doesn't use it, the first segment is always name=value
+    /// attribute names are case-insensitive and may be surrounded by whitespace,
+    /// e.g. `SECURE`, ` httponly`, `Max-Age = 1`
     pub fn is_set_cookie_option(st: &str) -> bool {
-        let st = st.to_lowercase();
+        let (name, value) = split_attribute(st);
+        let is = |x: &str| name.eq_ignore_ascii_case(x);
+
+        match value {
+            Some(_) => {
+                is("max-age")
+                    || is("domain")
+                    || is("path")
+                    || is("expires")
+                    || is("samesite")
+                    || is("priority")
+            }
+            None => is("httponly") || is("secure") || is("partitioned"),
+        }
+    }
 
-        st.starts_with("max-age=")
-            || st.starts_with("domain=")
-            || st.starts_with("path=")
-            // || st.starts_with("expires=")
-            || st.eq("httponly")
-            || st.eq("secure")
+    /// attribute names are case-insensitive, values are kept as-is
+    /// duplicated attributes are last-wins, as RFC 6265
+    fn apply_attribute(&mut self, st: &str) {
+        let (name, value) = split_attribute(st);
+        let is = |x: &str| name.eq_ignore_ascii_case(x);
+        // invalid values are skipped, earlier valid ones are kept
+        let ignored =
+            || tracing::debug!("ignored invalid {}: {:?}", name, value.unwrap_or_default());
+
+        if is("domain") {
+            let domain = value.unwrap_or_default();
+            let domain = domain.strip_prefix('.').unwrap_or(domain);
+            // empty domain is ignored, RFC 6265 5.2.3
+            if !domain.is_empty() {
+                self.domain.replace(domain.to_string());
+            }
+        } else if is("expires") {
+            match value.and_then(parse_expires) {
+                Some(expires) => {
+                    self.expires.replace(expires);
+                }
+                None => ignored(),
+            }
+        } else if is("max-age") {
+            // invalid value is ignored, not treated as 0 which means deletion
+            match value.and_then(|x| x.parse().ok()) {
+                Some(max_age) => {
+                    self.max_age.replace(max_age);
+                }
+                None => ignored(),
+            }
+        } else if is("path") {
+            let path = value.unwrap_or("/");
+            self.path.replace(path.to_string());
+        } else if is("httponly") {
+            self.http_only = true;
+        } else if is("secure") {
+            self.secure = true;
+        } else if is("partitioned") {
+            self.partitioned = true;
+        } else if is("samesite") {
+            match value.and_then(|s| s.parse().ok()) {
+                Some(same_site) => {
+                    self.same_site.replace(same_site);
+                }
+                None => ignored(),
+            }
+        } else if is("priority") {
+            match value.and_then(|s| s.parse().ok()) {
+                Some(priority) => {
+                    self.priority.replace(priority);
+                }
+                None => ignored(),
+            }
+        }
     }
 
     pub fn http_only(mut self, http_only: bool) -> Self {
@@ -80,12 +340,41 @@ impl SetCookieOptions {
         self
     }
 
+    /// `Partitioned` is always emitted with `Secure`, even if `secure` is false
+    pub fn partitioned(mut self, partitioned: bool) -> Self {
+        self.partitioned = partitioned;
+
+        self
+    }
+
+    /// emitted verbatim, not normalized
+    ///
+    /// RFC 6265 treats `Max-Age` <= 0 as "expire immediately",
+    /// but negative values are kept as-is for clients that treat them differently
     pub fn max_age(mut self, max_age: i64) -> Self {
         self.max_age.replace(max_age);
 
         self
     }
 
+    /// canonical deletion, `Max-Age=0`
+    pub fn expire_now(self) -> Self {
+        self.max_age(0)
+    }
+
+    pub fn expires(mut self, expires: impl Into<Expires>) -> Self {
+        self.expires.replace(expires.into());
+
+        self
+    }
+
+    /// `Max-Age` and `Expires` from the same ttl, for clients which ignore `Max-Age`
+    #[cfg(any(feature = "expires-time", feature = "expires-chrono"))]
+    pub fn ttl(self, seconds: i64, now: Expires) -> Self {
+        self.max_age(seconds)
+            .expires(crate::expires::add_seconds(now, seconds))
+    }
+
     pub fn domain(mut self, domain: impl Into<String>) -> Self {
         self.domain.replace(domain.into());
 
@@ -98,58 +387,170 @@ impl SetCookieOptions {
         self
     }
 
+    /// `SameSite=None` is always emitted with `Secure`, even if `secure` is false
     pub fn same_site(mut self, same_site: SameSite) -> Self {
         self.same_site.replace(same_site);
 
         self
     }
+
+    /// whether `Secure` is emitted, browsers reject `SameSite=None` and `Partitioned` without it
+    pub fn is_effectively_secure(&self) -> bool {
+        self.secure || self.partitioned || matches!(self.same_site, Some(SameSite::None))
+    }
+
+    /// `SameSite=None` with `Secure`, which browsers require for cross-site cookies
+    pub fn same_site_none(self) -> Self {
+        self.same_site(SameSite::None).secure(true)
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority.replace(priority);
+
+        self
+    }
+
+    pub fn unset_max_age(mut self) -> Self {
+        self.max_age = None;
+
+        self
+    }
+
+    pub fn unset_expires(mut self) -> Self {
+        self.expires = None;
+
+        self
+    }
+
+    pub fn unset_domain(mut self) -> Self {
+        self.domain = None;
+
+        self
+    }
+
+    pub fn unset_path(mut self) -> Self {
+        self.path = None;
+
+        self
+    }
+
+    pub fn unset_same_site(mut self) -> Self {
+        self.same_site = None;
+
+        self
+    }
+
+    pub fn unset_priority(mut self) -> Self {
+        self.priority = None;
+
+        self
+    }
+
+    /// `Strict` if origin is the request's own origin, otherwise `Lax`
+    ///
+    /// origin is `Origin` header value (e.g. `https://example.com:8080`),
+    /// scheme and host are of the request (e.g. `https`, `Host` header value)
+    /// scheme, host and port are compared, default ports are implied,
+    /// empty or `null` origin is cross-site
+    pub fn with_inferred_same_site(self, origin: &str, scheme: &str, host: &str) -> Self {
+        let same_origin = match origin.trim().split_once("://") {
+            Some((origin_scheme, authority)) => {
+                let authority = authority.split('/').next().unwrap_or_default();
+                let (origin_host, origin_port) = split_port(authority, origin_scheme);
+                let (host, port) = split_port(host, scheme);
+
+                origin_scheme.eq_ignore_ascii_case(scheme)
+                    && !origin_host.is_empty()
+                    && origin_host.eq_ignore_ascii_case(host)
+                    && origin_port == port
+            }
+            // `null` or empty
+            None => false,
+        };
+
+        if same_origin {
+            self.same_site(SameSite::Strict)
+        } else {
+            self.same_site(SameSite::Lax)
+        }
+    }
+}
+
+/// host and port of an authority, the port defaults by scheme
+fn split_port<'a>(authority: &'a str, scheme: &str) -> (&'a str, &'a str) {
+    let default_port = if scheme.eq_ignore_ascii_case("https") {
+        "443"
+    } else if scheme.eq_ignore_ascii_case("http") {
+        "80"
+    } else {
+        ""
+    };
+
+    // `]` after the last `:` is of an IPv6 address without port
+    match authority.rsplit_once(':') {
+        Some((host, "")) => (host, default_port),
+        Some((host, port)) if !port.contains(']') => (host, port),
+        _ => (authority, default_port),
+    }
 }
 
 impl<'a> From<Vec<&'a str>> for SetCookieOptions {
     fn from(xs: Vec<&'a str>) -> Self {
-        let mut options = SetCookieOptions {
-            domain: None,
-            max_age: None,
-            path: None,
-            http_only: false,
-            secure: false,
-            same_site: None,
-        };
+        let mut options = SetCookieOptions::new();
 
-        for st in xs.iter().map(|st| st.to_lowercase()) {
-            if st.starts_with("domain=") {
-                let domain = st.split('=').nth(1).unwrap_or_default();
-                options.domain.replace(domain.to_string());
-            } else if st.starts_with("max-age=") {
-                let max_age = st
-                    .split('=')
-                    .nth(1)
-                    .and_then(|x| x.parse().ok())
-                    .unwrap_or_default();
-                options.max_age.replace(max_age);
-            } else if st.starts_with("path=") {
-                let path = st.split('=').nth(1).unwrap_or("/");
-                options.path.replace(path.to_string());
-            } else if st.starts_with("httponly") {
-                options.http_only = true;
-            } else if st.starts_with("secure") {
-                options.secure = true;
-            } else if st.starts_with("samesite") {
-                let same_site = st.split('=').nth(1).and_then(|s| s.parse().ok());
-                if let Some(same_site) = same_site {
-                    options.same_site.replace(same_site);
-                }
-            }
+        for st in xs {
+            options.apply_attribute(st);
         }
 
         options
     }
 }
 
+/// a single cookie of `SetCookie`, see `SetCookie::entry`
+#[derive(Debug, Clone, Copy)]
+pub struct SetCookieEntry<'a> {
+    key: &'a str,
+    value: &'a str,
+    options: &'a SetCookieOptions,
+}
+
+impl<'a> SetCookieEntry<'a> {
+    pub fn key(&self) -> &'a str {
+        self.key
+    }
+
+    pub fn value(&self) -> &'a str {
+        self.value
+    }
+
+    pub fn options(&self) -> &'a SetCookieOptions {
+        self.options
+    }
+}
+
+/// exactly what is sent as the Set-Cookie header value
+impl Display for SetCookieEntry<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&fmt(self.key, self.value, self.options))
+    }
+}
+
+/// browsers commonly drop cookies larger than this, see `SetCookie::validate_size`
+pub const MAX_COOKIE_SIZE: usize = 4096;
+
+/// `\n` isn't allowed in `HeaderValue`, so `\t` is used,
+/// which is never emitted within a valid cookie
+///
+/// values aren't validated by `set`, so a cookie containing `\t` doesn't round-trip,
+/// check `validate_all` first if the values are untrusted
+pub const COMBINED_HEADER_SEPARATOR: &str = "\t";
+
+/// cookies are emitted in insertion order, replacing one keeps its position
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug, Clone, Default)]
 pub struct SetCookie {
-    inner: HashMap<String, (String, SetCookieOptions)>,
+    /// (name, value, options), unique by name, domain and path
+    inner: Vec<(String, String, SetCookieOptions)>,
 }
 
 impl SetCookie {
@@ -161,80 +562,399 @@ impl SetCookie {
         self.inner.is_empty()
     }
 
-    pub fn from_headers(headers: &HeaderMap) -> Self {
-        headers
-            .iter()
-            .filter(|(k, _)| k == &header::SET_COOKIE)
-            .filter_map(|(_, v)| v.to_str().ok())
-            .into()
+    /// counts cookies with the same name and different domain or path separately
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.inner.iter().any(|(k, _, _)| k == key)
     }
 
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+    }
+
+    /// first one if there are several cookies with the same name
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.inner.get(key).map(|(v, _)| v.as_str())
+        self.inner
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, v, _)| v.as_str())
     }
 
+    pub fn get_options(&self, key: &str) -> Option<&SetCookieOptions> {
+        self.get_full(key).map(|(_, options)| options)
+    }
+
+    pub fn get_full(&self, key: &str) -> Option<(&str, &SetCookieOptions)> {
+        self.inner
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, v, options)| (v.as_str(), options))
+    }
+
+    /// the first cookie named `key`, `Display` renders its Set-Cookie line
+    pub fn entry(&self, key: &str) -> Option<SetCookieEntry<'_>> {
+        self.inner
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(key, value, options)| SetCookieEntry {
+                key,
+                value,
+                options,
+            })
+    }
+
+    /// first one if there are several cookies with the same name
     pub fn take(&mut self, key: &str) -> Option<String> {
-        self.inner.remove(key).map(|(x, _)| x)
+        let i = self.inner.iter().position(|(k, _, _)| k == key)?;
+
+        Some(self.inner.remove(i).1)
+    }
+
+    /// whether any cookie has this value, regardless of name
+    pub fn contains_value(&self, value: &str) -> bool {
+        self.inner.iter().any(|(_, x, _)| x == value)
     }
 
+    /// replaces the cookie with the same name, domain, and path,
+    /// cookies with the same name and different domain or path are kept
     pub fn set(
         mut self,
         key: impl Into<String>,
         value: impl Into<String>,
         options: SetCookieOptions,
     ) -> Self {
-        self.inner.insert(key.into(), (value.into(), options));
+        self.set_mut(key, value, options);
 
         self
     }
 
+    /// same as `set`, with options configured from `SetCookieOptions::new()`
+    ///
+    /// e.g. `set_with("sid", id, |o| o.http_only(true).secure(true))`
+    pub fn set_with(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        f: impl FnOnce(SetCookieOptions) -> SetCookieOptions,
+    ) -> Self {
+        self.set(key, value, f(SetCookieOptions::new()))
+    }
+
+    /// same as `set`, but validates name, value and prefix rules
+    ///
+    /// `SameSite=None` without `Secure` is accepted, `Secure` is emitted for it anyway
+    pub fn try_set(
+        self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        options: SetCookieOptions,
+    ) -> Result<Self, CookieError> {
+        let key = key.into();
+        let value = value.into();
+
+        match validate(&key, &value) {
+            Ok(()) => {}
+            Err(CookieValidationError::InvalidValue) => return Err(CookieError::InvalidValue(key)),
+            Err(_) => return Err(CookieError::InvalidName(key)),
+        }
+
+        validate_prefix(&key, &options)?;
+
+        Ok(self.set(key, value, options))
+    }
+
+    /// value is percent-encoded, read it with `Cookie::get_decoded`
+    #[cfg(feature = "encode")]
+    pub fn set_encoded(
+        self,
+        key: impl Into<String>,
+        value: impl AsRef<str>,
+        options: SetCookieOptions,
+    ) -> Self {
+        self.set(key, crate::encode::encode(value.as_ref()), options)
+    }
+
+    /// value is signed, read it with `Cookie::get_signed`
+    #[cfg(feature = "signed")]
+    pub fn set_signed(
+        self,
+        signed: &crate::SignedCookie,
+        key: impl Into<String>,
+        value: impl AsRef<str>,
+        options: SetCookieOptions,
+    ) -> Self {
+        let key = key.into();
+        let value = signed.sign(&key, value.as_ref());
+        self.set(key, value, options)
+    }
+
+    /// value is encrypted, read it with `Cookie::get_encrypted`
+    #[cfg(feature = "encrypted")]
+    pub fn set_encrypted(
+        self,
+        encrypted: &crate::EncryptedCookie,
+        key: impl Into<String>,
+        value: impl AsRef<str>,
+        options: SetCookieOptions,
+    ) -> Self {
+        let key = key.into();
+        let value = encrypted.encrypt(&key, value.as_ref());
+        self.set(key, value, options)
+    }
+
+    /// deletion directive, empty value with `Max-Age=0`
+    ///
+    /// domain and path of options must be same as the cookie to delete
+    pub fn expire(self, key: impl Into<String>, options: SetCookieOptions) -> Self {
+        self.set(key, "", options.expire_now())
+    }
+
+    /// csrf token for double-submit pattern
+    ///
+    /// overrides options with `SameSite=Strict`, `Secure`, and without `HttpOnly` so javascript can read it
+    pub fn issue_csrf(
+        self,
+        key: impl Into<String>,
+        token: impl Into<String>,
+        options: SetCookieOptions,
+    ) -> Self {
+        let options = options
+            .same_site(SameSite::Strict)
+            .secure(true)
+            .http_only(false);
+
+        self.set(key, token, options)
+    }
+
+    /// all cookies with the name
     #[allow(dead_code)]
     pub fn remove(mut self, key: impl Into<String>) -> Self {
-        self.inner.remove(&key.into());
+        self.remove_mut(key);
+
+        self
+    }
+
+    /// sets `Secure` on all cookies if scheme is `https`, clears it if `http`,
+    /// other schemes are left as-is
+    pub fn secure_for_scheme(&mut self, scheme: &str) {
+        let secure = if scheme.eq_ignore_ascii_case("https") {
+            true
+        } else if scheme.eq_ignore_ascii_case("http") {
+            false
+        } else {
+            return;
+        };
+
+        for (_, _, options) in self.inner.iter_mut() {
+            options.secure = secure;
+        }
+    }
+
+    /// sets `Domain` on all cookies to last two labels of host, e.g. `a.example.com` -> `example.com`
+    ///
+    /// it's a heuristic, not aware of public suffixes like `co.uk`
+    ///
+    /// single label hosts and ip addresses can't have `Domain`, so it's cleared
+    pub fn domain_from_host(&mut self, host: &str) {
+        let domain = registrable_domain(host);
+
+        for (_, _, options) in self.inner.iter_mut() {
+            options.domain = domain.clone();
+        }
+    }
+
+    /// same as `set`, for `&mut SetCookie`
+    pub fn set_mut(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        options: SetCookieOptions,
+    ) -> &mut Self {
+        let key = key.into();
+        let value = value.into();
+
+        let same_scope = self
+            .inner
+            .iter_mut()
+            .find(|(k, _, o)| *k == key && o.domain == options.domain && o.path == options.path);
+
+        match same_scope {
+            Some(x) => *x = (key, value, options),
+            None => self.inner.push((key, value, options)),
+        }
+
+        self
+    }
+
+    /// same as `remove`, for `&mut SetCookie`
+    pub fn remove_mut(&mut self, key: impl Into<String>) -> &mut Self {
+        let key = key.into();
+
+        self.inner.retain(|(k, _, _)| *k != key);
 
         self
     }
 
-    /// SetHeaders::headers(set_cookie.iter());
-    pub fn iter(&self) -> impl Iterator<Item = (HeaderName, HeaderValue)> + '_ {
+    /// cookies a client should send to `host` and `path`, as RFC 6265 domain-match and path-match
+    ///
+    /// - cookies without domain are host-only, assumed to be set by `host`
+    /// - cookies without path, or whose path doesn't start with `/`, match every path,
+    ///   the default-path of the response that set them is unknown
+    /// - if names collide, the cookie with the longest path wins
+    pub fn applicable_to(&self, host: &str, path: &str, secure: bool) -> Cookie {
+        let path = if path.starts_with('/') { path } else { "/" };
+
+        self.inner
+            .iter()
+            .filter(|(_, _, options)| !options.is_effectively_secure() || secure)
+            .filter(|(_, _, options)| {
+                options
+                    .domain
+                    .as_deref()
+                    .is_none_or(|domain| domain_match(host, domain))
+            })
+            .map(|(key, value, options)| {
+                let cookie_path = options
+                    .path
+                    .as_deref()
+                    .filter(|x| x.starts_with('/'))
+                    .unwrap_or("/");
+                (key.as_str(), value.as_str(), cookie_path)
+            })
+            .filter(|(_, _, cookie_path)| path_match(path, cookie_path))
+            .sorted_by_key(|(_, _, cookie_path)| cookie_path.len())
+            .map(|(key, value, _)| (key, value))
+            .collect()
+    }
+
+    pub fn entries_vec(&self) -> Vec<(&str, &str, &SetCookieOptions)> {
+        self.inner
+            .iter()
+            .map(|(key, value, options)| (key.as_str(), value.as_str(), options))
+            .collect()
+    }
+
+    pub fn into_entries(self) -> Vec<(String, String, SetCookieOptions)> {
+        self.inner
+    }
+
+    /// checks names are tokens and values are cookie-octets, as RFC 6265
+    ///
+    /// in insertion order
+    pub fn validate_all(&self) -> Vec<(String, CookieValidationError)> {
         self.inner
             .iter()
-            .map(|(key, (value, options))| fmt(key, value, options))
-            .map(|st| (header::SET_COOKIE, st.parse().unwrap()))
+            .filter_map(|(key, value, _)| Some((key.clone(), validate(key, value).err()?)))
+            .collect()
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item = (HeaderName, HeaderValue)> {
+    /// checks the formatted length of each cookie, usually against `MAX_COOKIE_SIZE`
+    pub fn validate_size(&self, max_size: usize) -> Result<(), CookieSizeError> {
+        self.inner.iter().try_for_each(|(key, value, options)| {
+            let size = fmt(key, value, options).len();
+            if size > max_size {
+                Err(CookieSizeError {
+                    name: key.clone(),
+                    size,
+                    max_size,
+                })
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// checks `__Secure-` and `__Host-` prefix rules, browsers silently reject violating cookies
+    pub fn validate_prefixes(&self) -> Result<(), PrefixError> {
         self.inner
-            .into_iter()
-            .map(|(key, (value, options))| fmt(&key, &value, &options))
-            .map(|st| (header::SET_COOKIE, st.parse().unwrap()))
+            .iter()
+            .try_for_each(|(key, _, options)| validate_prefix(key, options))
+    }
+}
+
+fn registrable_domain(host: &str) -> Option<String> {
+    // ipv6 with or without port
+    if host.starts_with('[') {
+        return None;
+    }
+
+    let host = host.split(':').next().unwrap_or_default();
+
+    if host.parse::<core::net::Ipv4Addr>().is_ok() {
+        return None;
+    }
+
+    let labels = host
+        .trim_end_matches('.')
+        .rsplit('.')
+        .take(2)
+        .collect::<Vec<_>>();
+
+    match labels.as_slice() {
+        [tld, name] if !tld.is_empty() && !name.is_empty() => {
+            Some(format!("{}.{}", name, tld).to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+/// leading dot of `domain` is ignored
+fn domain_match(host: &str, domain: &str) -> bool {
+    let host = host.to_lowercase();
+    let domain = domain.trim_start_matches('.').to_lowercase();
+
+    if host == domain {
+        return true;
     }
+
+    // ip addresses only match themselves
+    let is_ip = host.starts_with('[') || host.parse::<core::net::Ipv4Addr>().is_ok();
+
+    !is_ip && host.ends_with(&domain) && host[..host.len() - domain.len()].ends_with('.')
+}
+
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
 }
 
 fn fmt(
     key: &str,
     value: &str,
-    SetCookieOptions {
+    options @ SetCookieOptions {
         domain,
+        expires,
         max_age,
         http_only,
-        secure,
+        partitioned,
         path,
         same_site,
+        priority,
+        ..
     }: &SetCookieOptions,
 ) -> String {
+    let secure = options.is_effectively_secure();
+    let expires = expires.as_ref().map(fmt_expires);
     let max_age = max_age.map(|x| x.to_string());
     let same_site = same_site.map(|x| x.as_str());
+    let priority = priority.map(|x| x.as_str());
 
     let capacity = 1
         + key.len()
         + value.len()
-        + domain.as_deref().map(|x| 2 + 6 + x.len()).unwrap_or(0)
-        + max_age.as_deref().map(|x| 2 + 7 + x.len()).unwrap_or(0)
-        + path.as_deref().map(|x| 2 + 4 + x.len()).unwrap_or(0)
-        + same_site.map(|x| 2 + 8 + x.len()).unwrap_or(0)
-        + if *http_only { 1 + 8 } else { 0 }
-        + if *secure { 1 + 6 } else { 0 };
+        + domain.as_deref().map(|x| 2 + 7 + x.len()).unwrap_or(0)
+        + expires.as_deref().map(|x| 2 + 8 + x.len()).unwrap_or(0)
+        + max_age.as_deref().map(|x| 2 + 8 + x.len()).unwrap_or(0)
+        + path.as_deref().map(|x| 2 + 5 + x.len()).unwrap_or(0)
+        + same_site.map(|x| 2 + 9 + x.len()).unwrap_or(0)
+        + priority.map(|x| 2 + 9 + x.len()).unwrap_or(0)
+        + if *http_only { 2 + 8 } else { 0 }
+        + if secure { 2 + 6 } else { 0 }
+        + if *partitioned { 2 + 11 } else { 0 };
 
     let mut base = String::with_capacity(capacity);
 
@@ -246,15 +966,22 @@ fn fmt(
 
     if let Some(domain) = domain {
         // base = format!("{}; Domain={}", base, domain);
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("Domain=");
         base.push_str(domain);
     }
 
+    if let Some(expires) = expires {
+        base.push_str("; ");
+
+        base.push_str("Expires=");
+        base.push_str(&expires);
+    }
+
     if let Some(max_age) = max_age {
         // base = format!("{}; Max-Age={}", base, max_age);
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("Max-Age=");
         base.push_str(&max_age);
@@ -262,34 +989,47 @@ fn fmt(
 
     if let Some(path) = path {
         // base = format!("{}; Path={}", base, path);
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("Path=");
-        base.push_str(&path);
+        base.push_str(path);
     }
 
     if let Some(same_site) = same_site {
         // base = format!("{}; SameSite={}", base, same_site.as_str())
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("SameSite=");
         base.push_str(same_site);
     }
 
+    if let Some(priority) = priority {
+        base.push_str("; ");
+
+        base.push_str("Priority=");
+        base.push_str(priority);
+    }
+
     if *http_only {
         // base = format!("{}; HttpOnly", base);
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("HttpOnly");
     }
 
-    if *secure {
+    if secure {
         // base = format!("{}; Secure", base);
-        base.push(';');
+        base.push_str("; ");
 
         base.push_str("Secure");
     }
 
+    if *partitioned {
+        base.push_str("; ");
+
+        base.push_str("Partitioned");
+    }
+
     #[cfg(debug_assertions)]
     {
         // assert_eq!(capacity, base.len());
@@ -301,53 +1041,87 @@ fn fmt(
     base
 }
 
+/// skips malformed values, see `try_parse_set_cookie`
+pub(crate) fn parse_set_cookie(st: &str) -> Option<(&str, &str, SetCookieOptions)> {
+    match try_parse_set_cookie(st) {
+        Ok(x) => Some(x),
+        Err(err) => {
+            tracing::debug!("skipped Set-Cookie {:?}: {}", st, err);
+            None
+        }
+    }
+}
+
+/// name=value is always the first segment, even if it looks like an attribute,
+/// the rest are attributes, as browsers parse, e.g. `path=1; Path=/`
+///
+/// - `MissingEquals` with the first segment if it hasn't `=`
+/// - `InvalidName` with the first segment if the name is empty
+fn try_parse_set_cookie(st: &str) -> Result<(&str, &str, SetCookieOptions), CookieError> {
+    // Set-Cookie: key=value; Max-Age=12345; Domain=eeeee.com; HttpOnly; Secure
+
+    let mut segments = st.split(';').map(|x| x.trim());
+    // `split` yields at least one segment
+    let key_value = segments.next().unwrap_or_default();
+
+    // value may contain `=`, e.g. base64 padding
+    let (key, value) = key_value
+        .split_once('=')
+        .ok_or_else(|| CookieError::MissingEquals(key_value.to_owned()))?;
+    let (key, value) = (key.trim(), value.trim());
+
+    // empty names are invalid
+    if key.is_empty() {
+        return Err(CookieError::InvalidName(key_value.to_owned()));
+    }
+
+    let mut options = SetCookieOptions::new();
+    for segment in segments {
+        options.apply_attribute(segment);
+    }
+
+    Ok((key, value, options))
+}
+
+/// trimmed name and value of an attribute, shared by `is_set_cookie_option` and `apply_attribute`
+fn split_attribute(st: &str) -> (&str, Option<&str>) {
+    match st.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (st.trim(), None),
+    }
+}
+
+/// see `parse_set_cookie`
 impl<A, I> From<I> for SetCookie
 where
     A: AsRef<str>,
     I: Iterator<Item = A>,
 {
     fn from(it: I) -> Self {
-        // Set-Cookie: key=value; Max-Age=12345; Domain=eeeee.com; HttpOnly; Secure
-
         let mut set_cookie = Self::new();
 
         for header_value in it {
-            let (options, key_value): (Vec<_>, Vec<_>) = header_value
-                .as_ref()
-                .split(';')
-                .map(|st| st.trim())
-                .partition(|st| SetCookieOptions::is_set_cookie_option(st));
-
-            // println!("options = {:?}", options);
-            // println!("key_value = {:?}", key_value);
-
-            let mut key_value = key_value.get(0).map(|st| st.split('='));
-
-            let key = key_value.as_mut().and_then(|st| st.next());
-            let value = key_value.as_mut().and_then(|st| st.next());
-
-            let (key, value) = match (key, value) {
-                (Some(key), Some(value)) => (key, value),
-                _ => continue,
-            };
-
-            // println!("key = {}", key);
-            // println!("value = {}", value);
-
-            set_cookie
-                .inner
-                .insert(key.to_string(), (value.to_string(), options.into()));
+            if let Some((key, value, options)) = parse_set_cookie(header_value.as_ref()) {
+                set_cookie.set_mut(key, value, options);
+            }
         }
 
         set_cookie
     }
 }
 
+#[test]
+fn set_cookie_value_with_equals() {
+    let set_cookie = SetCookie::from(["sid=YWJjZA==; Path=/"].iter());
+
+    assert_eq!(set_cookie.get("sid"), Some("YWJjZA=="));
+}
+
 #[test]
 fn set_cookie_from_header_values() {
     let header_value = "key=value; Max-Age=12345; Domain=eeee.com; HttpOnly; Secure; Path=/abcd/e";
 
-    let it = vec![header_value];
+    let it = [header_value];
 
     let set_cookie = SetCookie::from(it.iter());
 
@@ -367,6 +1141,7 @@ fn set_cookie_from_header_values() {
     assert_eq!(set_cookie, expected);
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn to_headers() {
     let set_cookie = SetCookie::new().set(
@@ -383,3 +1158,1355 @@ fn to_headers() {
 
     let _r = set_cookie.into_iter().collect::<Vec<_>>();
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn expire_now_and_negative_max_age() {
+    let options = SetCookieOptions::new().max_age(12345).expire_now();
+    assert_eq!(options.max_age, Some(0));
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().max_age(-5));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert!(header_value.to_str().unwrap().contains("Max-Age=-5"));
+}
+
+#[test]
+fn into_entries() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new().http_only(true))
+        .set("b", "2", SetCookieOptions::new().max_age(10));
+
+    let mut borrowed = set_cookie.entries_vec();
+    borrowed.sort_by(|x, y| x.0.cmp(y.0));
+
+    assert_eq!(
+        borrowed,
+        vec![
+            ("a", "1", &SetCookieOptions::new().http_only(true)),
+            ("b", "2", &SetCookieOptions::new().max_age(10)),
+        ]
+    );
+
+    let mut entries = set_cookie.into_entries();
+    entries.sort_by(|x, y| x.0.cmp(&y.0));
+
+    let expected = vec![
+        (
+            "a".to_owned(),
+            "1".to_owned(),
+            SetCookieOptions::new().http_only(true),
+        ),
+        (
+            "b".to_owned(),
+            "2".to_owned(),
+            SetCookieOptions::new().max_age(10),
+        ),
+    ];
+
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn set_cookie_skip_empty_name() {
+    let it = ["=noname; Path=/", "a=1"];
+
+    let set_cookie = SetCookie::from(it.iter());
+
+    assert_eq!(set_cookie.get(""), None);
+    assert_eq!(set_cookie.get("a"), Some("1"));
+
+    let set_cookie = SetCookie::from([" =noname", "a = 1 ; Path=/"].iter());
+
+    assert_eq!(set_cookie.entries_vec().len(), 1);
+    assert_eq!(set_cookie.get("a"), Some("1"));
+}
+
+#[test]
+fn contains_value() {
+    let set_cookie = SetCookie::new()
+        .set("a", "secret", SetCookieOptions::new())
+        .set("b", "public", SetCookieOptions::new());
+
+    assert!(set_cookie.contains_value("secret"));
+    assert!(!set_cookie.contains_value("leaked"));
+}
+
+#[test]
+fn inferred_same_site() {
+    let infer = |origin, scheme, host| {
+        SetCookieOptions::new()
+            .with_inferred_same_site(origin, scheme, host)
+            .same_site
+    };
+
+    let strict = Some(SameSite::Strict);
+    let lax = Some(SameSite::Lax);
+
+    assert_eq!(infer("https://example.com", "https", "example.com"), strict);
+    assert_eq!(
+        infer("http://localhost:8080", "http", "localhost:8080"),
+        strict
+    );
+    assert_eq!(
+        infer("https://EXAMPLE.com", "HTTPS", "example.com:443"),
+        strict
+    );
+    assert_eq!(infer("http://[::1]:8080", "http", "[::1]:8080"), strict);
+    assert_eq!(infer("http://[::1]", "http", "[::1]:80"), strict);
+
+    assert_eq!(infer("https://other.com", "https", "example.com"), lax);
+    // scheme and port are part of the origin
+    assert_eq!(infer("http://example.com", "https", "example.com"), lax);
+    assert_eq!(
+        infer("https://example.com:8443", "https", "example.com"),
+        lax
+    );
+    assert_eq!(
+        infer("http://localhost:8080", "http", "localhost:3000"),
+        lax
+    );
+    assert_eq!(infer("http://[::1]:8080", "http", "[::1]"), lax);
+    // opaque origin
+    assert_eq!(infer("null", "https", "example.com"), lax);
+    assert_eq!(infer("", "https", "example.com"), lax);
+    assert_eq!(infer("https://", "https", ""), lax);
+}
+
+#[test]
+fn set_cookie_first_segment_is_key_value() {
+    // attributes before name=value aren't reordered, as browsers
+    let set_cookie = SetCookie::from(["Secure; Path=/; id=1"].iter());
+    assert!(set_cookie.is_empty());
+
+    let set_cookie = SetCookie::from(["SameSite=Lax; key=v; Secure"].iter());
+    let expected = SetCookie::new().set("SameSite", "Lax", SetCookieOptions::new().secure(true));
+    assert_eq!(set_cookie, expected);
+
+    let set_cookie = SetCookie::from(["max-age=0; Max-Age=10; Path=/a"].iter());
+    let (value, options) = set_cookie.get_full("max-age").unwrap();
+    assert_eq!(value, "0");
+    assert_eq!(options.max_age, Some(10));
+    assert_eq!(options.path.as_deref(), Some("/a"));
+
+    let set_cookie = SetCookie::from(["Path=/; a=1"].iter());
+    let (value, options) = set_cookie.get_full("Path").unwrap();
+    assert_eq!(value, "/");
+    assert_eq!(options, &SetCookieOptions::new());
+    assert!(set_cookie.get("a").is_none());
+
+    let set_cookie = SetCookie::from(["a=1; path=1; Path=/"].iter());
+    let (value, options) = set_cookie.get_full("a").unwrap();
+    assert_eq!(value, "1");
+    assert_eq!(options.path.as_deref(), Some("/"));
+
+    assert!(matches!(
+        try_parse_set_cookie("Partitioned; a=1"),
+        Err(CookieError::MissingEquals(segment)) if segment == "Partitioned"
+    ));
+}
+
+#[test]
+fn set_cookie_options_preserve_value_case() {
+    let options = SetCookieOptions::from(vec!["Path=/API", "DOMAIN=ExAmple.com", "SameSite=Lax"]);
+
+    let expected = SetCookieOptions::new()
+        .path("/API")
+        .domain("ExAmple.com")
+        .same_site(SameSite::Lax);
+
+    assert_eq!(options, expected);
+}
+
+#[test]
+fn set_mut_and_remove_mut() {
+    let mut set_cookie = SetCookie::new();
+
+    set_cookie
+        .set_mut("a", "1", SetCookieOptions::new())
+        .set_mut("b", "2", SetCookieOptions::new())
+        .remove_mut("a");
+
+    let expected = SetCookie::new().set("b", "2", SetCookieOptions::new());
+
+    assert_eq!(set_cookie, expected);
+}
+
+#[cfg(feature = "expires-time")]
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_expires_round_trip() {
+    let expires = time::macros::datetime!(1994-11-06 08:49:37 UTC);
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().expires(expires));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "key=value; Expires=Sun, 06 Nov 1994 08:49:37 GMT"
+    );
+
+    let parsed = SetCookie::from([header_value.to_str().unwrap()].iter());
+
+    assert_eq!(parsed, set_cookie);
+}
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_expires_round_trip() {
+    use chrono::TimeZone;
+
+    let expires = chrono::Utc
+        .with_ymd_and_hms(1994, 11, 6, 8, 49, 37)
+        .unwrap();
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().expires(expires));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "key=value; Expires=Sun, 06 Nov 1994 08:49:37 GMT"
+    );
+
+    let parsed = SetCookie::from([header_value.to_str().unwrap()].iter());
+
+    assert_eq!(parsed, set_cookie);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn combined_header() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new().path("/").http_only(true))
+        .set("b", "2", SetCookieOptions::new().max_age(10));
+
+    let header_value = set_cookie.to_combined_header().unwrap();
+
+    assert_eq!(header_value.to_str().unwrap().split('\t').count(), 2);
+    assert_eq!(SetCookie::from_combined_header(&header_value), set_cookie);
+
+    // not validated, so `\t` in a value is taken as the separator
+    let set_cookie = SetCookie::new().set("a", "x\ty", SetCookieOptions::new());
+    assert!(!set_cookie.validate_all().is_empty());
+
+    let header_value = set_cookie.to_combined_header().unwrap();
+    assert_eq!(
+        SetCookie::from_combined_header(&header_value).get("a"),
+        Some("x")
+    );
+}
+
+#[test]
+fn set_cookie_options_max_age_sign() {
+    let options = SetCookieOptions::from(vec!["Max-Age=-5"]);
+    assert_eq!(options.max_age, Some(-5));
+
+    let options = SetCookieOptions::from(vec!["Max-Age=--5"]);
+    assert_eq!(options.max_age, None);
+
+    let options = SetCookieOptions::from(vec!["Max-Age="]);
+    assert_eq!(options.max_age, None);
+}
+
+#[test]
+fn set_cookie_options_max_age_not_a_number() {
+    let options = SetCookieOptions::from(vec!["Max-Age=notanumber"]);
+    assert_eq!(options.max_age, None);
+
+    // the previous valid value is kept
+    let options = SetCookieOptions::from(vec!["Max-Age=10", "Max-Age=notanumber"]);
+    assert_eq!(options.max_age, Some(10));
+
+    let set_cookie = SetCookie::from(["sid=1; Max-Age=notanumber; Secure"].into_iter());
+    let (value, options) = set_cookie.get_full("sid").unwrap();
+    assert_eq!(value, "1");
+    assert_eq!(options.max_age, None);
+    assert!(options.secure);
+}
+
+#[test]
+fn set_cookie_options_is_default() {
+    assert!(SetCookieOptions::new().is_default());
+    assert!(!SetCookieOptions::new().http_only(true).is_default());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn iter_prefixed_first() {
+    let set_cookie = SetCookie::new()
+        .set("theme", "dark", SetCookieOptions::new())
+        .set("lang", "ko", SetCookieOptions::new())
+        .set(
+            "__Host-sid",
+            "1",
+            SetCookieOptions::new().secure(true).path("/"),
+        );
+
+    let (_, first) = set_cookie.iter_prefixed_first().next().unwrap();
+
+    assert!(first.to_str().unwrap().starts_with("__Host-sid="));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn append_to_hyper_response() {
+    let mut response = hyper::Response::builder()
+        .header(header::SET_COOKIE, "existing=1")
+        .body(())
+        .unwrap();
+
+    let set_cookie = SetCookie::new().set("a", "1", SetCookieOptions::new()).set(
+        "b",
+        "2",
+        SetCookieOptions::new(),
+    );
+
+    set_cookie.append_to(response.headers_mut());
+
+    let parsed = SetCookie::from_headers(response.headers());
+
+    assert_eq!(
+        response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .count(),
+        3
+    );
+    assert_eq!(parsed.get("existing"), Some("1"));
+    assert_eq!(parsed.get("a"), Some("1"));
+    assert_eq!(parsed.get("b"), Some("2"));
+}
+
+#[test]
+fn set_cookie_options_secure_defaults() {
+    let options = SetCookieOptions::secure_defaults();
+
+    assert!(options.http_only);
+    assert!(options.secure);
+    assert_eq!(options.same_site, Some(SameSite::Lax));
+    assert_eq!(options.path.as_deref(), Some("/"));
+
+    let options = options.same_site(SameSite::None);
+
+    assert_eq!(options.same_site, Some(SameSite::None));
+}
+
+#[test]
+fn secure_for_scheme() {
+    let mut set_cookie = SetCookie::new().set("a", "1", SetCookieOptions::new()).set(
+        "b",
+        "2",
+        SetCookieOptions::new().secure(true),
+    );
+
+    set_cookie.secure_for_scheme("https");
+    assert!(set_cookie
+        .inner
+        .iter()
+        .all(|(_, _, options)| options.secure));
+
+    set_cookie.secure_for_scheme("http");
+    assert!(set_cookie
+        .inner
+        .iter()
+        .all(|(_, _, options)| !options.secure));
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut set_cookie = (0..100).fold(SetCookie::new(), |set_cookie, x| {
+        set_cookie.set(x.to_string(), x.to_string(), SetCookieOptions::new())
+    });
+
+    for x in 1..100 {
+        set_cookie.take(&x.to_string());
+    }
+
+    let capacity = set_cookie.inner.capacity();
+    set_cookie.shrink_to_fit();
+
+    assert!(set_cookie.inner.capacity() < capacity);
+    assert_eq!(set_cookie.get("0"), Some("0"));
+}
+
+#[test]
+fn set_cookie_options_diff() {
+    let a = SetCookieOptions::new().same_site(SameSite::Lax).path("/");
+    let b = SetCookieOptions::new()
+        .same_site(SameSite::Strict)
+        .secure(true)
+        .path("/")
+        .max_age(10);
+
+    assert_eq!(
+        a.diff(&b),
+        vec![
+            "Secure: false -> true",
+            "Max-Age: - -> 10",
+            "SameSite: Lax -> Strict"
+        ]
+    );
+    assert!(a.diff(&a.clone()).is_empty());
+}
+
+#[test]
+fn set_cookie_duplicated_attributes_last_wins() {
+    let it = ["key=value; Path=/a; Max-Age=1; Domain=a.com; Path=/b; Max-Age=2; Domain=b.com"];
+
+    let set_cookie = SetCookie::from(it.iter());
+
+    let expected = SetCookie::new().set(
+        "key",
+        "value",
+        SetCookieOptions::new()
+            .path("/b")
+            .max_age(2)
+            .domain("b.com"),
+    );
+
+    assert_eq!(set_cookie, expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn try_into_headers() {
+    let set_cookie = SetCookie::new()
+        .set("good", "1", SetCookieOptions::new())
+        .set("bad", "a\nb", SetCookieOptions::new());
+
+    let (key, _) = set_cookie.try_into_headers().unwrap_err();
+    assert_eq!(key, "bad");
+
+    let set_cookie = SetCookie::new().set("good", "1", SetCookieOptions::new());
+
+    let headers = set_cookie.try_into_headers().unwrap();
+    assert_eq!(headers.get_all(header::SET_COOKIE).iter().count(), 1);
+}
+
+#[test]
+fn domain_from_host() {
+    let mut set_cookie = SetCookie::new().set("a", "1", SetCookieOptions::new()).set(
+        "b",
+        "2",
+        SetCookieOptions::new().domain("other.com"),
+    );
+
+    set_cookie.domain_from_host("a.example.com:8080");
+    assert!(set_cookie
+        .inner
+        .iter()
+        .all(|(_, _, options)| options.domain.as_deref() == Some("example.com")));
+
+    set_cookie.domain_from_host("localhost");
+    assert!(set_cookie
+        .inner
+        .iter()
+        .all(|(_, _, options)| options.domain.is_none()));
+
+    assert_eq!(registrable_domain("127.0.0.1"), None);
+    assert_eq!(registrable_domain("[::1]:8080"), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn issue_csrf() {
+    let set_cookie = SetCookie::new().issue_csrf(
+        "csrf",
+        "token",
+        SetCookieOptions::new().http_only(true).path("/"),
+    );
+
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "csrf=token; Path=/; SameSite=Strict; Secure"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn into_iter_invalid_header_value() {
+    let set_cookie = || {
+        SetCookie::new()
+            .set("a", "1", SetCookieOptions::new())
+            .set("bad", "a\nb", SetCookieOptions::new())
+            .set("c", "3", SetCookieOptions::new())
+    };
+
+    assert_eq!(set_cookie().into_iter().count(), 2);
+
+    let mut it = set_cookie()
+        .into_iter()
+        .strategy(InvalidHeaderValueStrategy::Collect);
+
+    assert_eq!(it.by_ref().count(), 2);
+    assert_eq!(it.errors().len(), 1);
+    assert_eq!(it.errors()[0].0, "bad");
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic]
+fn into_iter_invalid_header_value_panic() {
+    let set_cookie = SetCookie::new().set("bad", "a\nb", SetCookieOptions::new());
+
+    set_cookie
+        .into_iter()
+        .strategy(InvalidHeaderValueStrategy::Panic)
+        .for_each(drop);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn from_header_values() {
+    let xs = [
+        HeaderValue::from_static("a=1; Path=/"),
+        HeaderValue::from_bytes(b"b=\xff").unwrap(),
+        HeaderValue::from_static("c=3"),
+    ];
+
+    let set_cookie = SetCookie::from_header_values(xs.iter());
+
+    let expected = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new().path("/"))
+        .set("c", "3", SetCookieOptions::new());
+
+    assert_eq!(set_cookie, expected);
+}
+
+#[cfg(feature = "expires-time")]
+#[test]
+fn set_cookie_options_ttl() {
+    let now = time::macros::datetime!(1994-11-06 08:49:37 UTC);
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().ttl(3600, now));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "key=value; Expires=Sun, 06 Nov 1994 09:49:37 GMT; Max-Age=3600"
+    );
+}
+
+#[cfg(all(feature = "expires-chrono", not(feature = "expires-time")))]
+#[test]
+fn set_cookie_options_ttl() {
+    use chrono::TimeZone;
+
+    let now = chrono::Utc
+        .with_ymd_and_hms(1994, 11, 6, 8, 49, 37)
+        .unwrap();
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().ttl(3600, now));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "key=value; Expires=Sun, 06 Nov 1994 09:49:37 GMT; Max-Age=3600"
+    );
+}
+
+#[test]
+fn fmt_attribute_separator() {
+    let options = SetCookieOptions::new()
+        .domain("example.com")
+        .max_age(10)
+        .path("/")
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .secure(true);
+
+    assert_eq!(
+        fmt("key", "value", &options),
+        "key=value; Domain=example.com; Max-Age=10; Path=/; SameSite=Lax; HttpOnly; Secure"
+    );
+}
+
+#[test]
+fn set_cookie_preserve_value_case() {
+    let it = ["key=value; Path=/API/Case; Domain=Example.COM"];
+
+    let set_cookie = SetCookie::from(it.iter());
+
+    let expected = SetCookie::new().set(
+        "key",
+        "value",
+        SetCookieOptions::new()
+            .path("/API/Case")
+            .domain("Example.COM"),
+    );
+
+    assert_eq!(set_cookie, expected);
+}
+
+#[cfg(not(any(feature = "expires-time", feature = "expires-chrono")))]
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_expires_round_trip() {
+    let expires = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+    let set_cookie = SetCookie::new().set("key", "value", SetCookieOptions::new().expires(expires));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "key=value; Expires=Sun, 06 Nov 1994 08:49:37 GMT"
+    );
+
+    let parsed = SetCookie::from([header_value.to_str().unwrap()].iter());
+
+    assert_eq!(parsed, set_cookie);
+}
+
+#[test]
+fn set_cookie_same_site_is_option() {
+    assert!(SetCookieOptions::is_set_cookie_option("SameSite=Lax"));
+
+    let expected = SetCookie::new().set(
+        "key",
+        "v",
+        SetCookieOptions::new()
+            .same_site(SameSite::Lax)
+            .secure(true),
+    );
+
+    let set_cookie = SetCookie::from(["key=v; SameSite=Lax; Secure"].iter());
+
+    assert_eq!(set_cookie, expected);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_same_name_different_scope() {
+    let set_cookie = SetCookie::new()
+        .set("sid", "1", SetCookieOptions::new().domain("a.com"))
+        .set("sid", "2", SetCookieOptions::new().domain("b.com"))
+        .set(
+            "sid",
+            "3",
+            SetCookieOptions::new().domain("a.com").path("/x"),
+        )
+        .set("sid", "4", SetCookieOptions::new().domain("a.com"));
+
+    assert_eq!(set_cookie.get("sid"), Some("4"));
+    assert_eq!(set_cookie.iter().count(), 3);
+
+    let entries = set_cookie.into_entries();
+
+    assert_eq!(
+        entries
+            .iter()
+            .map(|(_, value, _)| value.as_str())
+            .collect::<Vec<_>>(),
+        vec!["4", "2", "3"]
+    );
+}
+
+#[cfg(feature = "encode")]
+#[test]
+fn set_encoded() {
+    let set_cookie = SetCookie::new().set_encoded("a", "a; b=c", SetCookieOptions::new().path("/"));
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(header_value.to_str().unwrap(), "a=a%3B%20b%3Dc; Path=/");
+
+    let parsed = SetCookie::from_headers(&set_cookie.try_into_headers().unwrap());
+
+    assert_eq!(parsed.get("a"), Some("a%3B%20b%3Dc"));
+}
+
+#[test]
+fn same_site_none_implies_secure() {
+    let options = SetCookieOptions::new().same_site(SameSite::None);
+
+    assert_eq!(
+        fmt("key", "value", &options),
+        "key=value; SameSite=None; Secure"
+    );
+}
+
+#[test]
+fn same_site_none() {
+    let options = SetCookieOptions::new().same_site_none();
+
+    assert!(matches!(options.same_site, Some(SameSite::None)));
+    assert!(options.secure);
+
+    let st = fmt("key", "value", &options);
+    assert!(st.contains("SameSite=None"));
+    assert!(st.contains("Secure"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn set_cookie_options_serde_round_trip() {
+    let options = SetCookieOptions::new()
+        .http_only(true)
+        .max_age(10)
+        .domain("example.com")
+        .path("/")
+        .same_site(SameSite::Lax);
+
+    let json = serde_json::to_value(&options).unwrap();
+    assert_eq!(json["same_site"], "lax");
+
+    let deserialized: SetCookieOptions = serde_json::from_value(json).unwrap();
+    assert_eq!(deserialized, options);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn expire() {
+    let set_cookie = SetCookie::new().expire(
+        "sid",
+        SetCookieOptions::new().domain("example.com").path("/a"),
+    );
+    let (_, header_value) = set_cookie.iter().next().unwrap();
+
+    assert_eq!(
+        header_value.to_str().unwrap(),
+        "sid=; Domain=example.com; Max-Age=0; Path=/a"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validate_prefixes() {
+    let set_cookie = SetCookie::new()
+        .set(
+            "__Host-sid",
+            "1",
+            SetCookieOptions::new().secure(true).path("/"),
+        )
+        .set("__Secure-a", "1", SetCookieOptions::new().secure(true))
+        .set("theme", "dark", SetCookieOptions::new());
+
+    assert_eq!(set_cookie.validate_prefixes(), Ok(()));
+
+    let set_cookie = SetCookie::new().set(
+        "__Host-sid",
+        "1",
+        SetCookieOptions::new()
+            .secure(true)
+            .path("/")
+            .domain("example.com"),
+    );
+
+    assert_eq!(
+        set_cookie.validate_prefixes(),
+        Err(PrefixError::DomainNotAllowed("__Host-sid".to_owned()))
+    );
+
+    let set_cookie = SetCookie::new().set("__Secure-a", "1", SetCookieOptions::new());
+
+    assert_eq!(
+        set_cookie.validate_prefixes(),
+        Err(PrefixError::SecureRequired("__Secure-a".to_owned()))
+    );
+
+    // `Secure` is emitted for `Partitioned` and `SameSite=None`
+    let set_cookie = SetCookie::new()
+        .set("__Secure-a", "1", SetCookieOptions::new().partitioned(true))
+        .set(
+            "__Secure-b",
+            "1",
+            SetCookieOptions::new().same_site(SameSite::None),
+        );
+
+    assert_eq!(set_cookie.validate_prefixes(), Ok(()));
+    assert!(set_cookie
+        .iter()
+        .all(|(_, value)| value.to_str().unwrap().contains("; Secure")));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validate_all() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new())
+        .set("b", "x;y", SetCookieOptions::new())
+        .set("c d", "1", SetCookieOptions::new());
+
+    assert_eq!(
+        set_cookie.validate_all(),
+        vec![
+            ("b".to_owned(), CookieValidationError::InvalidValue),
+            ("c d".to_owned(), CookieValidationError::InvalidName),
+        ]
+    );
+    // still a valid header value, emitted as-is
+    assert_eq!(set_cookie.iter().count(), 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn iter_newline_in_value() {
+    let set_cookie = SetCookie::new().set("a", "1", SetCookieOptions::new()).set(
+        "b",
+        "x\ny",
+        SetCookieOptions::new(),
+    );
+
+    assert_eq!(set_cookie.iter().count(), 1);
+    assert_eq!(set_cookie.iter_prefixed_first().count(), 1);
+
+    let xs = set_cookie.try_iter().collect::<Vec<_>>();
+    assert!(xs[0].is_ok());
+    assert!(matches!(xs[1], Err(("b", _))));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn entry_display() {
+    let set_cookie = SetCookie::new().set(
+        "sid",
+        "1",
+        SetCookieOptions::new()
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .path("/")
+            .max_age(10)
+            .domain("example.com"),
+    );
+
+    let entry = set_cookie.entry("sid").unwrap();
+    assert_eq!(entry.key(), "sid");
+    assert_eq!(entry.value(), "1");
+    assert_eq!(
+        entry.to_string(),
+        "sid=1; Domain=example.com; Max-Age=10; Path=/; SameSite=Lax; HttpOnly; Secure"
+    );
+    assert_eq!(
+        entry.to_string(),
+        set_cookie.iter().next().unwrap().1.to_str().unwrap()
+    );
+    assert!(set_cookie.entry("missing").is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn emission_order() {
+    let set_cookie = SetCookie::new()
+        .set("c", "1", SetCookieOptions::new())
+        .set("a", "2", SetCookieOptions::new())
+        .set("b", "3", SetCookieOptions::new())
+        .set("c", "4", SetCookieOptions::new());
+
+    let expected = vec!["c=4", "a=2", "b=3"];
+
+    let xs = set_cookie
+        .iter()
+        .map(|(_, x)| x.to_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(xs, expected);
+
+    let xs = set_cookie
+        .into_iter()
+        .map(|(_, x)| x.to_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(xs, expected);
+}
+
+#[test]
+fn get_options_and_get_full() {
+    let set_cookie = SetCookie::from(["sid=1; Max-Age=10; Secure"].iter());
+
+    let options = set_cookie.get_options("sid").unwrap();
+    assert_eq!(options.max_age, Some(10));
+    assert!(options.secure);
+    assert!(!options.http_only);
+
+    let (value, options) = set_cookie.get_full("sid").unwrap();
+    assert_eq!(value, "1");
+    assert_eq!(options.max_age, Some(10));
+
+    assert!(set_cookie.get_options("missing").is_none());
+    assert!(set_cookie.get_full("missing").is_none());
+}
+
+#[test]
+fn validate_size() {
+    let options = SetCookieOptions::new().path("/").secure(true);
+
+    let set_cookie = SetCookie::new().set("sid", "a".repeat(4000), options.clone());
+    assert_eq!(set_cookie.validate_size(MAX_COOKIE_SIZE), Ok(()));
+
+    let set_cookie = set_cookie.set("large", "a".repeat(5000), options);
+    let err = set_cookie.validate_size(MAX_COOKIE_SIZE).unwrap_err();
+    assert_eq!(err.name, "large");
+    assert_eq!(err.size, "large=".len() + 5000 + "; Path=/; Secure".len());
+    assert_eq!(err.max_size, MAX_COOKIE_SIZE);
+}
+
+#[test]
+fn applicable_to_domain() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new().domain("example.com"))
+        .set("b", "2", SetCookieOptions::new().domain(".Example.com"))
+        .set("c", "3", SetCookieOptions::new().domain("sub.example.com"))
+        .set("d", "4", SetCookieOptions::new());
+
+    let cookie = set_cookie.applicable_to("sub.example.com", "/", false);
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]
+    );
+
+    let cookie = set_cookie.applicable_to("example.com", "/", false);
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2"), ("d", "4")]
+    );
+
+    let cookie = set_cookie.applicable_to("notexample.com", "/", false);
+    assert_eq!(cookie.iter_sorted().collect::<Vec<_>>(), vec![("d", "4")]);
+}
+
+#[test]
+fn applicable_to_path_and_secure() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new().path("/docs"))
+        .set("b", "2", SetCookieOptions::new().path("/docs/"))
+        .set("c", "3", SetCookieOptions::new().secure(true))
+        .set("sid", "root", SetCookieOptions::new().path("/"))
+        .set("sid", "docs", SetCookieOptions::new().path("/docs"));
+
+    let cookie = set_cookie.applicable_to("example.com", "/docs/web", true);
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("b", "2"), ("c", "3"), ("sid", "docs")]
+    );
+
+    let cookie = set_cookie.applicable_to("example.com", "/docs", false);
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("a", "1"), ("sid", "docs")]
+    );
+
+    // `Secure` is emitted for `SameSite=None` and `Partitioned`
+    let set_cookie = set_cookie
+        .set("d", "4", SetCookieOptions::new().same_site(SameSite::None))
+        .set("e", "5", SetCookieOptions::new().partitioned(true));
+    assert!(!set_cookie.get_options("d").unwrap().secure);
+
+    let cookie = set_cookie.applicable_to("example.com", "/", true);
+    assert_eq!(cookie.get("d"), Some("4"));
+    assert_eq!(cookie.get("e"), Some("5"));
+
+    let cookie = set_cookie.applicable_to("example.com", "/docsweb", false);
+    assert_eq!(
+        cookie.iter_sorted().collect::<Vec<_>>(),
+        vec![("sid", "root")]
+    );
+}
+
+#[test]
+fn set_cookie_name_looks_like_attribute() {
+    let set_cookie = SetCookie::from(["secure=1; HttpOnly"].iter());
+
+    let (value, options) = set_cookie.get_full("secure").unwrap();
+    assert_eq!(value, "1");
+    assert!(options.http_only);
+    assert!(!options.secure);
+
+    let set_cookie = SetCookie::from(["secure=1; HttpOnly; Secure"].iter());
+    let (value, options) = set_cookie.get_full("secure").unwrap();
+    assert_eq!(value, "1");
+    assert!(options.secure);
+
+    let set_cookie = SetCookie::from(["path=1; Path=/a"].iter());
+    let (value, options) = set_cookie.get_full("path").unwrap();
+    assert_eq!(value, "1");
+    assert_eq!(options.path.as_deref(), Some("/a"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn into_header_map() {
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new())
+        .set("b", "2", SetCookieOptions::new())
+        .set("c", "x\ny", SetCookieOptions::new());
+
+    let headers = set_cookie.into_header_map();
+
+    assert_eq!(headers.get_all(header::SET_COOKIE).iter().count(), 2);
+    assert_eq!(headers.len(), 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn priority_round_trip() {
+    let options = SetCookieOptions::new()
+        .priority(Priority::High)
+        .secure(true);
+    let set_cookie = SetCookie::new().set("sid", "1", options.clone());
+
+    let header_value = set_cookie.iter().next().unwrap().1;
+    assert_eq!(header_value, "sid=1; Priority=High; Secure");
+    assert_eq!(
+        SetCookie::from_header_values([&header_value].into_iter()),
+        set_cookie
+    );
+
+    assert!(SetCookieOptions::is_set_cookie_option("priority=low"));
+    let options = SetCookieOptions::from(vec!["Priority=LOW"]);
+    assert_eq!(options.priority, Some(Priority::Low));
+    let options = SetCookieOptions::from(vec!["Priority=urgent"]);
+    assert_eq!(options.priority, None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn partitioned_round_trip() {
+    let set_cookie = SetCookie::new().set(
+        "sid",
+        "1",
+        SetCookieOptions::new()
+            .partitioned(true)
+            .secure(true)
+            .path("/"),
+    );
+
+    let header_value = set_cookie.iter().next().unwrap().1;
+    assert_eq!(header_value, "sid=1; Path=/; Secure; Partitioned");
+    assert_eq!(
+        SetCookie::from_header_values([&header_value].into_iter()),
+        set_cookie
+    );
+
+    // `Secure` is implied
+    let options = SetCookieOptions::new().partitioned(true);
+    assert_eq!(fmt("sid", "1", &options), "sid=1; Secure; Partitioned");
+    assert!(!options.is_default());
+
+    assert!(SetCookieOptions::is_set_cookie_option("Partitioned"));
+    assert!(SetCookieOptions::from(vec!["partitioned"]).partitioned);
+}
+
+#[test]
+fn try_set() {
+    let set_cookie = SetCookie::new()
+        .try_set("sid", "1", SetCookieOptions::new().same_site_none())
+        .unwrap();
+    assert_eq!(set_cookie.get("sid"), Some("1"));
+
+    assert!(matches!(
+        SetCookie::new().try_set("", "1", SetCookieOptions::new()),
+        Err(CookieError::InvalidName(x)) if x.is_empty()
+    ));
+    assert!(matches!(
+        SetCookie::new().try_set("s id", "1", SetCookieOptions::new()),
+        Err(CookieError::InvalidName(x)) if x == "s id"
+    ));
+    assert!(matches!(
+        SetCookie::new().try_set("sid", "a;b", SetCookieOptions::new()),
+        Err(CookieError::InvalidValue(x)) if x == "sid"
+    ));
+    assert!(matches!(
+        SetCookie::new().try_set("__Host-sid", "1", SetCookieOptions::new().secure(true)),
+        Err(CookieError::PrefixViolation(PrefixError::PathMustBeRoot(_)))
+    ));
+
+    let set_cookie = SetCookie::new()
+        .try_set(
+            "sid",
+            "1",
+            SetCookieOptions::new().same_site(SameSite::None),
+        )
+        .unwrap();
+    assert!(set_cookie
+        .entry("sid")
+        .unwrap()
+        .to_string()
+        .contains("; Secure"));
+}
+
+#[test]
+fn same_site_and_priority_from_str_invalid() {
+    assert!(matches!(
+        "bogus".parse::<SameSite>(),
+        Err(CookieError::InvalidSameSite(x)) if x == "bogus"
+    ));
+    assert!(matches!(
+        "urgent".parse::<Priority>(),
+        Err(CookieError::InvalidPriority(x)) if x == "urgent"
+    ));
+}
+
+#[test]
+fn set_cookie_options_unset() {
+    let options = SetCookieOptions::secure_defaults()
+        .max_age(10)
+        .domain("example.com")
+        .priority(Priority::High);
+
+    let reissued = options.clone().unset_max_age().unset_domain();
+    assert_eq!(reissued.max_age, None);
+    assert_eq!(reissued.domain, None);
+    assert_eq!(reissued.path.as_deref(), Some("/"));
+    assert_eq!(options.max_age, Some(10));
+
+    let cleared = options
+        .http_only(false)
+        .secure(false)
+        .unset_path()
+        .unset_same_site()
+        .unset_priority()
+        .unset_max_age()
+        .unset_domain()
+        .unset_expires();
+    assert!(cleared.is_default());
+}
+
+#[test]
+fn set_cookie_len_and_contains() {
+    let set_cookie = SetCookie::new();
+    assert_eq!(set_cookie.len(), 0);
+    assert!(!set_cookie.contains("a"));
+
+    let set_cookie =
+        set_cookie
+            .set("a", "1", SetCookieOptions::new())
+            .set("b", "2", SetCookieOptions::new());
+
+    assert_eq!(set_cookie.len(), 2);
+    assert!(set_cookie.contains("a"));
+    assert!(set_cookie.contains("b"));
+    assert!(!set_cookie.contains("c"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_try_from_headers() {
+    let mut headers = HeaderMap::new();
+    headers.append(header::SET_COOKIE, HeaderValue::from_static("a=1; Secure"));
+    headers.append(header::SET_COOKIE, HeaderValue::from_static("b=2"));
+
+    let set_cookie = SetCookie::try_from_headers(&headers).unwrap();
+    assert_eq!(set_cookie, SetCookie::from_headers(&headers));
+    assert_eq!(set_cookie.get("a"), Some("1"));
+    assert_eq!(set_cookie.get("b"), Some("2"));
+
+    let mut missing_equals = headers.clone();
+    missing_equals.append(header::SET_COOKIE, HeaderValue::from_static("Secure; a=1"));
+    assert!(matches!(
+        SetCookie::try_from_headers(&missing_equals),
+        Err(CookieError::MissingEquals(x)) if x == "Secure"
+    ));
+    assert_eq!(SetCookie::from_headers(&missing_equals).len(), 2);
+
+    let mut empty_name = headers.clone();
+    empty_name.append(header::SET_COOKIE, HeaderValue::from_static(" =1; Secure"));
+    assert!(matches!(
+        SetCookie::try_from_headers(&empty_name),
+        Err(CookieError::InvalidName(x)) if x == "=1"
+    ));
+    assert_eq!(SetCookie::from_headers(&empty_name).len(), 2);
+}
+
+#[test]
+fn set_cookie_set_with() {
+    let set_cookie = SetCookie::new()
+        .set_with("a", "1", |o| o.http_only(true).secure(true))
+        .set_with("b", "2", |o| o);
+
+    assert_eq!(
+        set_cookie.get_options("a"),
+        Some(&SetCookieOptions::new().http_only(true).secure(true))
+    );
+    assert_eq!(set_cookie.get_options("b"), Some(&SetCookieOptions::new()));
+}
+
+#[test]
+fn set_cookie_options_domain_leading_dot() {
+    let options = SetCookieOptions::from(vec!["Domain=.example.com"]);
+    assert_eq!(options.domain.as_deref(), Some("example.com"));
+
+    let options = SetCookieOptions::from(vec!["Domain=example.com"]);
+    assert_eq!(options.domain.as_deref(), Some("example.com"));
+
+    // only a single dot
+    let options = SetCookieOptions::from(vec!["Domain=..example.com"]);
+    assert_eq!(options.domain.as_deref(), Some(".example.com"));
+}
+
+#[test]
+fn set_cookie_options_empty_domain_ignored() {
+    for st in ["Domain=", "Domain=.", "Domain", "Domain = . "] {
+        let options = SetCookieOptions::from(vec![st]);
+        assert_eq!(options.domain, None, "{}", st);
+
+        let options = SetCookieOptions::from(vec!["Domain=example.com", st]);
+        assert_eq!(options.domain.as_deref(), Some("example.com"), "{}", st);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+fn capture_events(f: impl FnOnce()) -> Vec<String> {
+    use std::sync::{Arc, Mutex};
+    use tracing::{field, span, Event, Metadata, Subscriber};
+
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    struct Message<'a>(&'a mut String);
+
+    impl field::Visit for Message<'_> {
+        fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.0.lock().unwrap().push(message);
+        }
+        fn enter(&self, _: &span::Id) {}
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    tracing::subscriber::with_default(Capture(events.clone()), f);
+
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_malformed_emits_events() {
+    let events = capture_events(|| {
+        let set_cookie = SetCookie::from(["a=1", "Secure", "=2"].into_iter());
+        assert_eq!(set_cookie.len(), 1);
+    });
+
+    assert_eq!(
+        events,
+        [
+            r#"skipped Set-Cookie "Secure": missing `=` in `Secure`"#,
+            r#"skipped Set-Cookie "=2": invalid cookie name `=2`"#
+        ]
+    );
+
+    let events = capture_events(|| {
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("a=1; b"));
+        assert!(Cookie::from_headers(header::COOKIE, &headers).is_none());
+    });
+
+    assert_eq!(events, ["failed to parse Cookie: missing `=` in `b`"]);
+
+    let events = capture_events(|| {
+        let set_cookie = SetCookie::from(
+            ["a=1; Expires; SameSite=Loose; Priority=Urgent; max-age=1.5; Secure"].into_iter(),
+        );
+        assert_eq!(
+            set_cookie.get_full("a"),
+            Some(("1", &SetCookieOptions::new().secure(true)))
+        );
+    });
+
+    assert_eq!(
+        events,
+        [
+            r#"ignored invalid Expires: """#,
+            r#"ignored invalid SameSite: "Loose""#,
+            r#"ignored invalid Priority: "Urgent""#,
+            r#"ignored invalid max-age: "1.5""#,
+        ]
+    );
+}
+
+#[test]
+fn set_cookie_options_flags_case_insensitive() {
+    for st in [
+        "SECURE",
+        "Secure",
+        " secure",
+        "HttpOnly",
+        "httponly",
+        "HTTPONLY ",
+        "Max-Age = 1",
+    ] {
+        assert!(SetCookieOptions::is_set_cookie_option(st), "{st}");
+    }
+    assert!(!SetCookieOptions::is_set_cookie_option("secure=1"));
+    assert!(!SetCookieOptions::is_set_cookie_option("path"));
+
+    let expected = SetCookieOptions::new().http_only(true).secure(true);
+    for it in [
+        ["key=value; SECURE; HttpOnly"],
+        ["key=value;secure;httponly"],
+        ["key=value; HTTPONLY ;  Secure"],
+    ] {
+        let set_cookie = SetCookie::from(it.into_iter());
+        assert_eq!(set_cookie.get_options("key"), Some(&expected), "{it:?}");
+    }
+
+    assert_eq!(
+        SetCookieOptions::from(vec!["SECURE", "httponly"]),
+        SetCookieOptions::from(vec!["secure", "HttpOnly"])
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn set_cookie_options_eq_and_hash() {
+    use std::collections::HashSet;
+
+    let a = SetCookieOptions::new()
+        .http_only(true)
+        .same_site(SameSite::Lax);
+    let b = SetCookieOptions::from(vec!["HttpOnly", "SameSite=Lax"]);
+    let c = a.clone().secure(true);
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_ne!(SameSite::Lax, SameSite::Strict);
+
+    let set = HashSet::from([a, b, c]);
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn same_site_display_round_trip() {
+    assert_eq!(SameSite::Lax.to_string(), "Lax");
+    assert_eq!(SameSite::None.as_str(), "None");
+    assert_eq!("lax".parse::<SameSite>().unwrap(), SameSite::Lax);
+    assert_eq!("STRICT".parse::<SameSite>().unwrap(), SameSite::Strict);
+
+    for same_site in [SameSite::Strict, SameSite::Lax, SameSite::None] {
+        assert_eq!(
+            same_site.to_string().parse::<SameSite>().unwrap(),
+            same_site
+        );
+    }
+}
+
+#[test]
+fn priority_display_round_trip() {
+    assert_eq!(Priority::High.to_string(), "High");
+    assert_eq!(Priority::Low.as_str(), "Low");
+    assert_eq!("High".parse::<Priority>().unwrap(), Priority::High);
+    assert_eq!("medium".parse::<Priority>().unwrap(), Priority::Medium);
+
+    for priority in [Priority::Low, Priority::Medium, Priority::High] {
+        assert_eq!(priority.to_string().parse::<Priority>().unwrap(), priority);
+    }
+}
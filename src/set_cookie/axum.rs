@@ -0,0 +1,47 @@
+use std::convert::Infallible;
+
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+
+use crate::SetCookie;
+
+/// cookies which can't be a `HeaderValue` are skipped, see `SetCookie::iter`
+impl IntoResponseParts for SetCookie {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        self.append_to(res.headers_mut());
+
+        Ok(res)
+    }
+}
+
+impl IntoResponse for SetCookie {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+#[test]
+fn test_into_response_parts() {
+    use http::header;
+
+    use crate::SetCookieOptions;
+
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new())
+        .set("b", "2", SetCookieOptions::new().http_only(true))
+        .set("c", "x\ny", SetCookieOptions::new());
+
+    let res = (set_cookie.clone(), "body").into_response();
+    let xs = res
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|x| x.to_str().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(xs, vec!["a=1", "b=2; HttpOnly"]);
+
+    let res = set_cookie.into_response();
+    assert_eq!(res.headers().get_all(header::SET_COOKIE).iter().count(), 2);
+}
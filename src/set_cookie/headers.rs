@@ -0,0 +1,210 @@
+use http::{
+    header::{self, HeaderName, InvalidHeaderValue},
+    HeaderMap, HeaderValue,
+};
+use itertools::Itertools;
+
+use super::{fmt, try_parse_set_cookie, COMBINED_HEADER_SEPARATOR};
+use crate::{CookieError, SetCookie, SetCookieOptions};
+
+impl SetCookie {
+    /// skips malformed values, see `try_from_headers`
+    ///
+    /// there is no `From<&HeaderMap>` as it would overlap with `From<Iterator>`
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::from_header_values(headers.get_all(header::SET_COOKIE).iter())
+    }
+
+    /// same as `from_headers`, but fails on the first value that `from_headers` would skip
+    ///
+    /// - `NotVisibleAscii` if failed to `header_value.to_str()`
+    /// - `MissingEquals` if the first segment hasn't `=`, e.g. `Secure; a=1`
+    /// - `InvalidName` if the name is empty, e.g. `=1; Secure`
+    pub fn try_from_headers(headers: &HeaderMap) -> Result<Self, CookieError> {
+        let mut set_cookie = Self::new();
+
+        for header_value in headers.get_all(header::SET_COOKIE) {
+            let st = header_value
+                .to_str()
+                .map_err(|_| CookieError::NotVisibleAscii)?;
+            let (key, value, options) = try_parse_set_cookie(st)?;
+
+            set_cookie.set_mut(key, value, options);
+        }
+
+        Ok(set_cookie)
+    }
+
+    /// skips non visible ascii values
+    pub fn from_header_values<'a>(it: impl Iterator<Item = &'a HeaderValue>) -> Self {
+        it.filter_map(|v| match v.to_str() {
+            Ok(x) => Some(x),
+            Err(_) => {
+                tracing::debug!("skipped non visible ascii Set-Cookie: {:?}", v);
+                None
+            }
+        })
+        .into()
+    }
+
+    /// SetHeaders::headers(set_cookie.iter());
+    /// cookies which can't be a `HeaderValue` are skipped, see `validate_all`
+    pub fn iter(&self) -> impl Iterator<Item = (HeaderName, HeaderValue)> + '_ {
+        self.inner
+            .iter()
+            .filter_map(|(key, value, options)| to_header(key, value, options))
+    }
+
+    /// same as `iter`, but yields the name of cookies which can't be a `HeaderValue`
+    pub fn try_iter(
+        &self,
+    ) -> impl Iterator<Item = Result<(HeaderName, HeaderValue), (&str, InvalidHeaderValue)>> + '_
+    {
+        self.inner.iter().map(|(key, value, options)| {
+            HeaderValue::try_from(fmt(key, value, options))
+                .map(|header_value| (header::SET_COOKIE, header_value))
+                .map_err(|err| (key.as_str(), err))
+        })
+    }
+
+    /// same as `iter`, but `__Host-` and `__Secure-` prefixed cookies come first
+    pub fn iter_prefixed_first(&self) -> impl Iterator<Item = (HeaderName, HeaderValue)> + '_ {
+        self.inner
+            .iter()
+            .sorted_by_key(|(key, _, _)| !is_prefixed(key))
+            .filter_map(|(key, value, options)| to_header(key, value, options))
+    }
+
+    /// fails with the name of the first cookie which can't be a `HeaderValue`
+    pub fn try_into_headers(self) -> Result<HeaderMap, (String, InvalidHeaderValue)> {
+        let mut headers = HeaderMap::new();
+
+        for (key, value, options) in self.inner {
+            match HeaderValue::try_from(fmt(&key, &value, &options)) {
+                Ok(header_value) => headers.append(header::SET_COOKIE, header_value),
+                Err(err) => return Err((key, err)),
+            };
+        }
+
+        Ok(headers)
+    }
+
+    /// appends, not inserts, so existing `Set-Cookie` headers are kept
+    pub fn append_to(&self, headers: &mut HeaderMap) {
+        for (header_name, header_value) in self.iter() {
+            headers.append(header_name, header_value);
+        }
+    }
+
+    /// one `SET_COOKIE` per cookie, invalid cookies are skipped like `iter`
+    pub fn into_header_map(self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        self.append_to(&mut headers);
+        headers
+    }
+
+    /// non-standard, only for internal transports
+    ///
+    /// all cookies in one value, separated by `COMBINED_HEADER_SEPARATOR`,
+    /// cookies containing the separator are split apart by `from_combined_header`
+    pub fn to_combined_header(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        self.inner
+            .iter()
+            .map(|(key, value, options)| fmt(key, value, options))
+            .join(COMBINED_HEADER_SEPARATOR)
+            .try_into()
+    }
+
+    /// inverse of `to_combined_header`
+    pub fn from_combined_header(header_value: &HeaderValue) -> Self {
+        header_value
+            .to_str()
+            .unwrap_or_default()
+            .split(COMBINED_HEADER_SEPARATOR)
+            .into()
+    }
+}
+
+/// what `IntoIter` does with a cookie which can't be a `HeaderValue`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidHeaderValueStrategy {
+    #[default]
+    Skip,
+    Panic,
+    /// skips and collects errors, see `IntoIter::errors`
+    Collect,
+}
+
+pub struct IntoIter {
+    inner: std::vec::IntoIter<(String, String, SetCookieOptions)>,
+    strategy: InvalidHeaderValueStrategy,
+    errors: Vec<(String, InvalidHeaderValue)>,
+}
+
+impl IntoIter {
+    pub fn strategy(mut self, strategy: InvalidHeaderValueStrategy) -> Self {
+        self.strategy = strategy;
+
+        self
+    }
+
+    /// names and errors of skipped cookies, only with `InvalidHeaderValueStrategy::Collect`
+    pub fn errors(&self) -> &[(String, InvalidHeaderValue)] {
+        &self.errors
+    }
+}
+
+impl Iterator for IntoIter {
+    type Item = (HeaderName, HeaderValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // an invalid cookie doesn't end the iteration
+        for (key, value, options) in self.inner.by_ref() {
+            let err = match HeaderValue::try_from(fmt(&key, &value, &options)) {
+                Ok(header_value) => return Some((header::SET_COOKIE, header_value)),
+                Err(err) => err,
+            };
+
+            match self.strategy {
+                InvalidHeaderValueStrategy::Skip => {}
+                InvalidHeaderValueStrategy::Panic => {
+                    panic!("invalid header value of cookie `{}`: {}", key, err)
+                }
+                InvalidHeaderValueStrategy::Collect => self.errors.push((key, err)),
+            }
+        }
+
+        None
+    }
+}
+
+impl IntoIterator for SetCookie {
+    type Item = (HeaderName, HeaderValue);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.inner.into_iter(),
+            strategy: Default::default(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+fn to_header(
+    key: &str,
+    value: &str,
+    options: &SetCookieOptions,
+) -> Option<(HeaderName, HeaderValue)> {
+    match HeaderValue::try_from(fmt(key, value, options)) {
+        Ok(header_value) => Some((header::SET_COOKIE, header_value)),
+        Err(err) => {
+            tracing::warn!("invalid header value of cookie `{}`: {}", key, err);
+            None
+        }
+    }
+}
+
+fn is_prefixed(key: &str) -> bool {
+    key.starts_with("__Host-") || key.starts_with("__Secure-")
+}
@@ -0,0 +1,47 @@
+use poem::{IntoResponse, Response};
+
+use crate::SetCookie;
+
+impl SetCookie {
+    /// appends to the existing `Set-Cookie` headers of `resp`
+    pub fn apply_to(&self, resp: &mut Response) {
+        self.append_to(resp.headers_mut());
+    }
+}
+
+/// cookies which can't be a `HeaderValue` are skipped, see `SetCookie::iter`
+impl IntoResponse for SetCookie {
+    fn into_response(self) -> Response {
+        let mut resp = Response::default();
+        self.apply_to(&mut resp);
+        resp
+    }
+}
+
+#[test]
+fn test_apply_to() {
+    use http::header;
+
+    use crate::SetCookieOptions;
+
+    let set_cookie = SetCookie::new()
+        .set("a", "1", SetCookieOptions::new())
+        .set("b", "2", SetCookieOptions::new().http_only(true))
+        .set("c", "x\ny", SetCookieOptions::new());
+
+    let mut resp = Response::builder()
+        .header(header::SET_COOKIE, "z=0")
+        .body("body");
+    set_cookie.apply_to(&mut resp);
+
+    let xs = resp
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|x| x.to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(xs, vec!["z=0", "a=1", "b=2; HttpOnly"]);
+
+    let resp = set_cookie.into_response();
+    assert_eq!(resp.headers().get_all(header::SET_COOKIE).iter().count(), 2);
+}
@@ -0,0 +1,78 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// signs cookie values with HMAC-SHA256, so tampering is detectable
+///
+/// the signed value is `{tag}.{value}`, the tag is bound to the cookie name
+#[derive(Clone)]
+pub struct SignedCookie {
+    mac: Hmac<Sha256>,
+}
+
+impl std::fmt::Debug for SignedCookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignedCookie").finish_non_exhaustive()
+    }
+}
+
+impl SignedCookie {
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            // hmac accepts keys of any length
+            mac: Hmac::new_from_slice(secret.as_ref()).unwrap(),
+        }
+    }
+
+    fn mac(&self, name: &str, value: &str) -> Hmac<Sha256> {
+        let mut mac = self.mac.clone();
+        mac.update(name.as_bytes());
+        mac.update(b"=");
+        mac.update(value.as_bytes());
+        mac
+    }
+
+    pub fn sign(&self, name: &str, value: &str) -> String {
+        let tag = self.mac(name, value).finalize().into_bytes();
+
+        format!("{}.{}", URL_SAFE_NO_PAD.encode(tag), value)
+    }
+
+    /// returns the value only if the tag is valid, compared in constant time
+    pub fn verify(&self, name: &str, signed_value: &str) -> Option<String> {
+        let (tag, value) = signed_value.split_once('.')?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+
+        self.mac(name, value)
+            .verify_slice(&tag)
+            .ok()
+            .map(|_| value.to_owned())
+    }
+}
+
+#[test]
+fn test_signed_valid() {
+    let signed = SignedCookie::new("secret");
+    let value = signed.sign("sid", "a.b=c");
+
+    assert_eq!(signed.verify("sid", &value), Some("a.b=c".to_owned()));
+    assert!(crate::validate::is_cookie_value(&value));
+}
+
+#[test]
+fn test_signed_tampered() {
+    let signed = SignedCookie::new("secret");
+    let value = signed.sign("sid", "user=1");
+    let tampered = value.replace("user=1", "user=2");
+
+    assert_eq!(signed.verify("sid", &tampered), None);
+    assert_eq!(signed.verify("other", &value), None);
+    assert_eq!(signed.verify("sid", "user=1"), None);
+}
+
+#[test]
+fn test_signed_wrong_key() {
+    let value = SignedCookie::new("secret").sign("sid", "1");
+
+    assert_eq!(SignedCookie::new("other").verify("sid", &value), None);
+}
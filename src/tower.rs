@@ -0,0 +1,83 @@
+use std::task::{Context, Poll};
+
+use ::tower::{Layer, Service};
+use http::{header, Request};
+
+use crate::Cookie;
+
+/// parses the cookie header once and inserts `Cookie` into request extensions
+///
+/// requests without a valid cookie header are passed through untouched
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CookieLayer;
+
+impl CookieLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for CookieLayer {
+    type Service = CookieService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CookieService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CookieService<S> {
+    inner: S,
+}
+
+impl<S> CookieService<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, B> Service<Request<B>> for CookieService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        match Cookie::try_from_headers(header::COOKIE, req.headers()) {
+            Ok(Some(cookie)) => {
+                req.extensions_mut().insert(cookie);
+            }
+            Ok(None) => {}
+            Err(err) => tracing::debug!("failed to parse cookie header: {err}"),
+        }
+
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn cookie_layer() {
+    use ::tower::{service_fn, ServiceExt};
+
+    let svc = CookieLayer::new().layer(service_fn(|req: Request<()>| async move {
+        Ok::<_, std::convert::Infallible>(req.extensions().get::<Cookie>().cloned())
+    }));
+
+    let req = Request::builder()
+        .header(header::COOKIE, "a=1; b=2")
+        .body(())
+        .unwrap();
+    let cookie = svc.clone().oneshot(req).await.unwrap().unwrap();
+    assert_eq!(cookie.get("a"), Some("1"));
+    assert_eq!(cookie.get("b"), Some("2"));
+
+    let req = Request::builder().body(()).unwrap();
+    assert!(svc.oneshot(req).await.unwrap().is_none());
+}
@@ -0,0 +1,93 @@
+use crate::CookieValidationError;
+#[cfg(not(feature = "std"))]
+use alloc::borrow::ToOwned;
+
+use crate::{PrefixError, SetCookieOptions};
+
+/// RFC 6265 cookie-name, which is RFC 2616 token
+pub(crate) fn is_cookie_name(st: &str) -> bool {
+    !st.is_empty()
+        && st
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b))
+}
+
+/// RFC 6265 cookie-value, optionally double quoted
+pub(crate) fn is_cookie_value(st: &str) -> bool {
+    let st = st
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .unwrap_or(st);
+
+    st.bytes().all(is_cookie_octet)
+}
+
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+pub(crate) fn validate(name: &str, value: &str) -> Result<(), CookieValidationError> {
+    if name.is_empty() {
+        return Err(CookieValidationError::EmptyName);
+    }
+
+    if !is_cookie_name(name) {
+        return Err(CookieValidationError::InvalidName);
+    }
+
+    if !is_cookie_value(value) {
+        return Err(CookieValidationError::InvalidValue);
+    }
+
+    Ok(())
+}
+
+/// `__Secure-` requires `Secure` as emitted, see `SetCookieOptions::is_effectively_secure`,
+/// `__Host-` requires `Secure`, no `Domain` and `Path=/`
+pub(crate) fn validate_prefix(name: &str, options: &SetCookieOptions) -> Result<(), PrefixError> {
+    let is_host = name.starts_with("__Host-");
+    let is_secure = name.starts_with("__Secure-");
+
+    if (is_host || is_secure) && !options.is_effectively_secure() {
+        return Err(PrefixError::SecureRequired(name.to_owned()));
+    }
+
+    if is_host && options.domain.is_some() {
+        return Err(PrefixError::DomainNotAllowed(name.to_owned()));
+    }
+
+    if is_host && options.path.as_deref() != Some("/") {
+        return Err(PrefixError::PathMustBeRoot(name.to_owned()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate() {
+    assert!(validate("sid", "abc").is_ok());
+    assert!(validate("sid", "\"abc\"").is_ok());
+    assert!(validate("sid", "").is_ok());
+
+    assert_eq!(validate("", "abc"), Err(CookieValidationError::EmptyName));
+    assert_eq!(
+        validate("s id", "abc"),
+        Err(CookieValidationError::InvalidName)
+    );
+    assert_eq!(
+        validate("s=id", "abc"),
+        Err(CookieValidationError::InvalidName)
+    );
+    assert_eq!(
+        validate("sid", "a;b"),
+        Err(CookieValidationError::InvalidValue)
+    );
+    assert_eq!(
+        validate("sid", "a b"),
+        Err(CookieValidationError::InvalidValue)
+    );
+    assert_eq!(
+        validate("sid", "a,b"),
+        Err(CookieValidationError::InvalidValue)
+    );
+}